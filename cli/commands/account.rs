@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::Network;
+use crate::{errors::CliError, helpers::UserConfig, Network};
 use snarkvm::prelude::{Address, PrivateKey, ViewKey};
 
 use anyhow::Result;
@@ -32,13 +32,16 @@ pub enum Account {
         /// Seed the RNG with a numeric value
         #[clap(short = 's', long)]
         seed: Option<u64>,
+        /// Saves the account under the given name, which commands accept in place of its private key or address
+        #[clap(long)]
+        save: Option<String>,
     },
 }
 
 impl Account {
     pub fn parse(self) -> Result<String> {
         match self {
-            Self::New { seed } => {
+            Self::New { seed, save } => {
                 // Sample a new Aleo account.
                 let private_key = match seed {
                     Some(seed) => PrivateKey::<Network>::new(&mut ChaChaRng::seed_from_u64(seed))?,
@@ -52,6 +55,14 @@ impl Account {
                 output += &format!(" {:>12}  {}\n", "View Key".cyan().bold(), view_key);
                 output += &format!(" {:>12}  {}", "Address".cyan().bold(), address);
 
+                // Save the Aleo account, if a name was given.
+                if let Some(name) = save {
+                    UserConfig::open()
+                        .and_then(|config| config.save_account(&name, &private_key))
+                        .map_err(|error| CliError::User(error.to_string()))?;
+                    output += &format!("\n {:>12}  {}", "Saved As".cyan().bold(), name);
+                }
+
                 Ok(output)
             }
         }
@@ -66,7 +77,7 @@ mod tests {
     #[test]
     fn test_new() {
         for _ in 0..3 {
-            let account = Account::New { seed: None };
+            let account = Account::New { seed: None, save: None };
             assert!(account.parse().is_ok());
         }
     }
@@ -89,7 +100,7 @@ mod tests {
             "Address".cyan().bold(),
             "aleo1whnlxsgnhc8ywft2l4nu9hywedspcjpwcsgg490ckz34tthqsupqdh5z64"
         );
-        let account = Account::New { seed };
+        let account = Account::New { seed, save: None };
         let actual = account.parse().unwrap();
         assert_eq!(expected, actual);
     }
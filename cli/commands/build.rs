@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{Aleo, Network};
+use crate::{helpers::remember_program_id, Aleo, Network};
 use snarkvm::package::Package;
 
 use anyhow::Result;
@@ -44,6 +44,7 @@ impl Build {
         if package.is_build_required::<Aleo>() {
             Self::build(&package, self.endpoint, self.offline)?;
         }
+        remember_program_id(&package.program_id().to_string());
 
         // Prepare the path string.
         let path_string = format!("(in \"{}\")", path.display());
@@ -0,0 +1,141 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{commands::CLI, helpers::UserConfig, Network};
+use snarkvm::{file::Manifest, prelude::ProgramID};
+
+use anyhow::{anyhow, Result};
+use clap::{CommandFactory, Parser};
+use clap_complete::Shell;
+use core::str::FromStr;
+use indexmap::IndexSet;
+use std::path::Path;
+
+/// Generates shell completions for the Aleo CLI (run with `aleo --quiet completions <shell>`).
+#[derive(Debug, Parser)]
+pub struct Completions {
+    /// The shell to generate completions for.
+    #[clap(arg_enum, required_unless_present_any = &["program-ids", "account-names"])]
+    shell: Option<Shell>,
+    /// Lists the program IDs to complete, which is used by the completion scripts.
+    #[clap(long, hide = true)]
+    program_ids: bool,
+    /// Lists the account names to complete, which is used by the completion scripts.
+    #[clap(long, hide = true)]
+    account_names: bool,
+}
+
+impl Completions {
+    /// The options that are completed dynamically, with the flag listing their values.
+    const DYNAMIC_OPTIONS: &'static [(&'static str, &'static [&'static str])] = &[
+        ("--program-ids", &["--auto-claim"]),
+        ("--account-names", &["--producer", "--private-key"]),
+    ];
+
+    /// Returns the completion script for the specified shell, or the values to complete.
+    pub fn parse(self) -> Result<String> {
+        if self.program_ids {
+            let recent = UserConfig::open()
+                .map(|config| config.recent_program_ids())
+                .unwrap_or_default();
+            return Ok(Self::program_ids(&std::env::current_dir()?, &recent).join("\n"));
+        }
+        if self.account_names {
+            let names = UserConfig::open()
+                .map(|config| config.account_names())
+                .unwrap_or_default();
+            return Ok(names.join("\n"));
+        }
+        let shell = self
+            .shell
+            .ok_or_else(|| anyhow!("Please specify the shell to generate completions for"))?;
+
+        let mut buffer = Vec::new();
+        clap_complete::generate(shell, &mut CLI::command(), "aleo", &mut buffer);
+        let mut script = String::from_utf8(buffer)?;
+        // Complete the program IDs and account names dynamically, in the shells that allow it.
+        script += &Self::dynamic_hook(shell);
+        Ok(script)
+    }
+
+    /// Returns the program IDs known in the given program directory, which are the program itself
+    /// and its imports, followed by the given recently used program IDs and the programs of the network.
+    fn program_ids(directory: &Path, recent: &[String]) -> Vec<String> {
+        let mut program_ids = IndexSet::new();
+        if let Ok(manifest) = Manifest::<Network>::open(directory) {
+            program_ids.insert(manifest.program_id().to_string());
+        }
+        if let Ok(entries) = std::fs::read_dir(directory.join("imports")) {
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if ProgramID::<Network>::from_str(&file_name).is_ok() {
+                    program_ids.insert(file_name);
+                }
+            }
+        }
+        program_ids.extend(recent.iter().cloned());
+        program_ids.insert("credits.aleo".to_string());
+        program_ids.into_iter().collect()
+    }
+
+    /// Returns the script completing the options that are completed dynamically, for the given shell.
+    fn dynamic_hook(shell: Shell) -> String {
+        let list = |flag: &str| format!("aleo --quiet completions {flag} 2>/dev/null");
+        match shell {
+            Shell::Bash => {
+                let cases = Self::DYNAMIC_OPTIONS
+                    .iter()
+                    .map(|(flag, options)| {
+                        format!(
+                            r#"        {options})
+            COMPREPLY=( $(compgen -W "$({list})" -- "${{COMP_WORDS[COMP_CWORD]}}") )
+            ;;
+"#,
+                            options = options.join("|"),
+                            list = list(flag),
+                        )
+                    })
+                    .collect::<String>();
+                format!(
+                    r#"
+_aleo_dynamic() {{
+    case "${{COMP_WORDS[COMP_CWORD-1]}}" in
+{cases}        *)
+            _aleo "$@"
+            ;;
+    esac
+}}
+
+complete -F _aleo_dynamic -o bashdefault -o default aleo
+"#
+                )
+            }
+            Shell::Fish => Self::DYNAMIC_OPTIONS
+                .iter()
+                .flat_map(|(flag, options)| {
+                    options.iter().map(move |option| {
+                        format!(
+                            "complete -c aleo -l {} -r -f -a \"({})\"\n",
+                            option.trim_start_matches("--"),
+                            list(flag)
+                        )
+                    })
+                })
+                .collect(),
+            _ => String::new(),
+        }
+    }
+}
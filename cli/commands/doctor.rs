@@ -0,0 +1,409 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+#[cfg(feature = "node")]
+use crate::helpers::{http_request, NodeInfo, SupportedNetwork};
+use crate::{
+    errors::UpdaterError,
+    helpers::{aleo_directory, Updater},
+    Aleo,
+    Network,
+};
+#[cfg(feature = "node")]
+use snarkvm::prelude::Network as _;
+use snarkvm::{file::Manifest, package::Package, prelude::Address};
+
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use colored::Colorize;
+use core::time::Duration;
+use std::{
+    convert::TryFrom,
+    net::UdpSocket,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Diagnoses the Aleo CLI environment.
+#[derive(Debug, Parser)]
+pub struct Doctor {
+    /// The node endpoint to check, such as `127.0.0.1:4180`.
+    #[cfg(feature = "node")]
+    #[clap(long, default_value = "127.0.0.1:4180")]
+    endpoint: String,
+    /// The NTP server to check the system clock against.
+    #[clap(long, default_value = "pool.ntp.org:123")]
+    time_server: String,
+}
+
+impl Doctor {
+    /// The maximum offset of the system clock that is not reported.
+    const MAX_CLOCK_SKEW: Duration = Duration::from_secs(10);
+    /// The minimum free disk space for the storage paths, in bytes.
+    const MIN_DISK_SPACE: u64 = 1024 * 1024 * 1024;
+    /// The timeout of the network checks.
+    const NETWORK_TIMEOUT: Duration = Duration::from_secs(3);
+
+    /// Runs the environment checks, and prints a fix for each failed check.
+    pub fn parse(self) -> Result<String> {
+        // Derive the program directory path.
+        let path = std::env::current_dir()?;
+
+        let mut output = format!(
+            "🩺 Checking the Aleo environment {}\n\n",
+            format!("(in \"{}\")", path.display()).dimmed()
+        );
+        output += &Self::check_release();
+        #[cfg(feature = "node")]
+        {
+            output += &Self::check_node(&self.endpoint);
+        }
+        output += &Self::check_package(&path);
+        output += &Self::check_parameters();
+        output += &Self::check_disk_space(&path);
+        output += &Self::check_clock(&self.time_server);
+        Ok(output)
+    }
+
+    /// Checks that the release channel is reachable, and that the CLI is up to date.
+    fn check_release() -> String {
+        let current_version = env!("CARGO_PKG_VERSION");
+        match Updater::update_available() {
+            Ok(latest_version) => Self::warn(
+                &format!("Aleo v{current_version} is out of date (v{latest_version} is available)"),
+                "Run `aleo update` to update to the latest version",
+            ),
+            Err(UpdaterError::OldReleaseVersion(..)) => Self::pass(&format!("Aleo v{current_version} is up to date")),
+            Err(error) => Self::warn(
+                &format!("Failed to reach the release channel ({error})"),
+                "Check the network connection, or any proxy settings",
+            ),
+        }
+    }
+
+    /// Checks that the node at the given endpoint is reachable, and compatible with this version.
+    #[cfg(feature = "node")]
+    fn check_node(endpoint: &str) -> String {
+        let info = match Self::node_info(endpoint) {
+            Ok(info) => info,
+            Err(error) => {
+                return Self::warn(
+                    &format!("Failed to reach the node at {endpoint} ({error})"),
+                    "Run `aleo node start` in a program directory, or pass the node with `--endpoint`",
+                );
+            }
+        };
+        match info.check_compatibility::<Network>() {
+            Ok(()) => Self::pass(&format!("The node at {endpoint} runs the compatible v{}", info.version)),
+            Err(error) => Self::fail(
+                &format!("The node at {endpoint} is incompatible ({error})"),
+                "Update the node or the CLI, so that their versions and networks match",
+            ),
+        }
+    }
+
+    /// Fetches the node information from the node at the given endpoint.
+    #[cfg(feature = "node")]
+    fn node_info(endpoint: &str) -> Result<NodeInfo> {
+        let network = SupportedNetwork::from_id(Network::ID)?.name();
+        let body = http_request(
+            endpoint,
+            "GET",
+            &format!("/{network}/node/info"),
+            None,
+            Self::NETWORK_TIMEOUT,
+        )?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Checks that the given path is a valid Aleo package, and that its build is usable.
+    fn check_package(path: &Path) -> String {
+        // Ensure the manifest file exists.
+        if !Manifest::<Network>::exists_at(path) {
+            return Self::warn(
+                &format!(
+                    "Not in an Aleo program directory (missing '{}')",
+                    Manifest::<Network>::file_name()
+                ),
+                "Run `aleo new <name>` to create a program, or run this command in a program directory",
+            );
+        }
+
+        // Load the package.
+        let package = match Package::<Network>::open(path) {
+            Ok(package) => package,
+            Err(error) => {
+                return Self::fail(
+                    &format!("Failed to load the program ({error})"),
+                    &format!("Fix the program or '{}' file", Manifest::<Network>::file_name()),
+                );
+            }
+        };
+        let mut output = Self::pass(&format!("Loaded the program '{}'", package.program_id()));
+
+        // Ensure the development account in the manifest is consistent.
+        let manifest = package.manifest_file();
+        match Address::try_from(manifest.development_private_key()) {
+            Ok(address) if address == *manifest.development_address() => {
+                output += &Self::pass("The development account in the manifest is valid")
+            }
+            _ => {
+                output += &Self::fail(
+                    "The development private key in the manifest does not match its address",
+                    &format!(
+                        "Fix the development account in '{}', or create a new program with `aleo new <name>`",
+                        Manifest::<Network>::file_name()
+                    ),
+                )
+            }
+        }
+
+        // Ensure the build directory is writable, or can be created.
+        let build_directory = path.join("build");
+        match std::fs::metadata(&build_directory) {
+            Ok(metadata) if metadata.permissions().readonly() => {
+                output += &Self::fail(
+                    &format!("The build directory is read-only (\"{}\")", build_directory.display()),
+                    "Make the build directory writable, or run `aleo clean`",
+                )
+            }
+            Ok(_) => output += &Self::pass("The build directory is writable"),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => match std::fs::metadata(path) {
+                Ok(metadata) if !metadata.permissions().readonly() => {
+                    output += &Self::pass("The build directory can be created")
+                }
+                _ => {
+                    output += &Self::fail(
+                        "The build directory cannot be created, as the program directory is read-only",
+                        "Make the program directory writable",
+                    )
+                }
+            },
+            Err(error) => {
+                output += &Self::fail(
+                    &format!("Failed to read the build directory ({error})"),
+                    "Check the permissions of the build directory, or run `aleo clean`",
+                )
+            }
+        }
+
+        // Check if the program requires a build.
+        match package.is_build_required::<Aleo>() {
+            true => {
+                output += &Self::warn(
+                    "The program build is out of date",
+                    "Run `aleo build` to build the program",
+                )
+            }
+            false => output += &Self::pass("The program build is up to date"),
+        }
+        output
+    }
+
+    /// Checks that the downloaded parameter files are not truncated.
+    ///
+    /// The parameter files are checked against their checksums by snarkVM when they are loaded,
+    /// so an empty file, which is left behind by an interrupted download, is the failure to catch early.
+    fn check_parameters() -> String {
+        let directory = match Self::parameters_directory() {
+            Some(directory) => directory,
+            None => {
+                return Self::warn(
+                    "Failed to locate the home directory, which stores the parameter files",
+                    "Set the HOME environment variable",
+                );
+            }
+        };
+        let entries = match std::fs::read_dir(&directory) {
+            Ok(entries) => entries,
+            Err(_) => {
+                return Self::warn(
+                    &format!("No parameter files are downloaded yet (in \"{}\")", directory.display()),
+                    "The parameter files are downloaded on the first build or run, which requires network access",
+                );
+            }
+        };
+
+        let mut count = 0;
+        let mut truncated = Vec::new();
+        for entry in entries.flatten() {
+            match entry.metadata() {
+                Ok(metadata) if metadata.is_file() && metadata.len() == 0 => truncated.push(entry.file_name()),
+                Ok(metadata) if metadata.is_file() => count += 1,
+                _ => {}
+            }
+        }
+        match truncated.is_empty() {
+            true => Self::pass(&format!("Found {count} parameter files")),
+            false => Self::fail(
+                &format!(
+                    "Found {} truncated parameter files (in \"{}\")",
+                    truncated.len(),
+                    directory.display()
+                ),
+                &format!(
+                    "Delete {}, so they are downloaded again",
+                    truncated
+                        .iter()
+                        .map(|name| name.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            ),
+        }
+    }
+
+    /// Returns the directory that snarkVM downloads the parameter files to.
+    fn parameters_directory() -> Option<PathBuf> {
+        Some(aleo_directory()?.join("resources"))
+    }
+
+    /// Checks that the storage paths, which are the program directory and the parameters directory,
+    /// have enough free disk space.
+    fn check_disk_space(path: &Path) -> String {
+        let mut output = String::new();
+        for storage in std::iter::once(path.to_path_buf()).chain(Self::parameters_directory()) {
+            // The parameters directory may not exist yet, so check the closest existing ancestor.
+            let existing = match storage.ancestors().find(|ancestor| ancestor.exists()) {
+                Some(existing) => existing,
+                None => continue,
+            };
+            match fs2::available_space(existing) {
+                Ok(space) if space < Self::MIN_DISK_SPACE => {
+                    output += &Self::warn(
+                        &format!(
+                            "Only {} MiB of disk space is free for \"{}\"",
+                            space / (1024 * 1024),
+                            storage.display()
+                        ),
+                        &format!(
+                            "Free up at least {} MiB of disk space",
+                            Self::MIN_DISK_SPACE / (1024 * 1024)
+                        ),
+                    )
+                }
+                Ok(space) => {
+                    output += &Self::pass(&format!(
+                        "{} MiB of disk space is free for \"{}\"",
+                        space / (1024 * 1024),
+                        storage.display()
+                    ))
+                }
+                Err(error) => {
+                    output += &Self::warn(
+                        &format!("Failed to check the disk space for \"{}\" ({error})", storage.display()),
+                        "Check the free disk space manually",
+                    )
+                }
+            }
+        }
+        output
+    }
+
+    /// Checks that the system clock is in sync with the given NTP server.
+    fn check_clock(time_server: &str) -> String {
+        match Self::clock_offset(time_server) {
+            Ok(offset) if offset.abs() > Self::MAX_CLOCK_SKEW.as_secs_f64() => Self::warn(
+                &format!("The system clock is off by {offset:.1}s from {time_server}"),
+                "Synchronize the system clock, for example by enabling NTP",
+            ),
+            Ok(offset) => Self::pass(&format!(
+                "The system clock is in sync ({offset:+.1}s from {time_server})"
+            )),
+            Err(error) => Self::warn(
+                &format!("Failed to reach the time server {time_server} ({error})"),
+                "Check the network connection, or allow outgoing UDP traffic to port 123",
+            ),
+        }
+    }
+
+    /// Returns the offset of the system clock from the given NTP server, in seconds.
+    fn clock_offset(time_server: &str) -> Result<f64> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.set_read_timeout(Some(Self::NETWORK_TIMEOUT))?;
+        socket.connect(time_server)?;
+
+        // Send an SNTP client request, and wait for the response.
+        let mut request = [0u8; 48];
+        request[0] = 0x1B;
+        let sent = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        socket.send(&request)?;
+        let mut response = [0u8; 48];
+        let length = socket.recv(&mut response)?;
+        let received = SystemTime::now().duration_since(UNIX_EPOCH)?;
+
+        // Compare the server time with the midpoint of the round trip.
+        let server_time = ntp_timestamp(&response[..length]).ok_or_else(|| anyhow!("the response is malformed"))?;
+        let local_time = (sent + received) / 2;
+        Ok(server_time.as_secs_f64() - local_time.as_secs_f64())
+    }
+
+    /// Returns the line for a passed check.
+    fn pass(check: &str) -> String {
+        format!(" ✅ {check}\n")
+    }
+
+    /// Returns the lines for a check that needs attention, with the suggested fix.
+    fn warn(check: &str, fix: &str) -> String {
+        format!(" ⚠️  {check}\n    {} {}\n", "↳".yellow(), fix.yellow())
+    }
+
+    /// Returns the lines for a failed check, with the suggested fix.
+    fn fail(check: &str, fix: &str) -> String {
+        format!(" ❌ {check}\n    {} {}\n", "↳".red(), fix.red())
+    }
+}
+
+/// Returns the transmit timestamp of the given SNTP response, as the time since the UNIX epoch.
+fn ntp_timestamp(response: &[u8]) -> Option<Duration> {
+    /// The seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+    const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+    let timestamp = response.get(40..48)?;
+    let seconds = u64::from(u32::from_be_bytes([
+        timestamp[0],
+        timestamp[1],
+        timestamp[2],
+        timestamp[3],
+    ]));
+    let fraction = u64::from(u32::from_be_bytes([
+        timestamp[4],
+        timestamp[5],
+        timestamp[6],
+        timestamp[7],
+    ]));
+    // An unsynchronized server responds with a zero timestamp.
+    let seconds = seconds.checked_sub(NTP_UNIX_OFFSET)?;
+    Some(Duration::from_secs(seconds) + Duration::from_nanos((fraction * 1_000_000_000) >> 32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ntp_timestamp() {
+        // The timestamp is read from the transmit timestamp field.
+        let mut response = [0u8; 48];
+        response[40..44].copy_from_slice(&(2_208_988_800u32 + 1_000).to_be_bytes());
+        response[44..48].copy_from_slice(&(1u32 << 31).to_be_bytes());
+        assert_eq!(Some(Duration::from_millis(1_000_500)), ntp_timestamp(&response));
+
+        // A zero or truncated timestamp is rejected.
+        assert_eq!(None, ntp_timestamp(&[0u8; 48]));
+        assert_eq!(None, ntp_timestamp(&response[..44]));
+    }
+}
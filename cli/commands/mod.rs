@@ -23,12 +23,23 @@ pub use build::*;
 mod clean;
 pub use clean::*;
 
+mod completions;
+pub use completions::*;
+
+mod doctor;
+pub use doctor::*;
+
 mod new;
 pub use new::*;
 
+#[cfg(feature = "node")]
 mod node;
+#[cfg(feature = "node")]
 pub use node::*;
 
+mod payment;
+pub use payment::*;
+
 mod run;
 pub use run::*;
 
@@ -44,6 +55,9 @@ pub struct CLI {
     /// Specify the verbosity [options: 0, 1, 2, 3]
     #[clap(default_value = "2", short, long)]
     pub verbosity: u8,
+    /// Prints only the result of the command, without colors or update notices
+    #[clap(long)]
+    pub quiet: bool,
     /// Specify a subcommand.
     #[clap(subcommand)]
     pub command: Command,
@@ -57,10 +71,17 @@ pub enum Command {
     Build(Build),
     #[clap(name = "clean")]
     Clean(Clean),
+    #[clap(name = "completions")]
+    Completions(Completions),
+    #[clap(name = "doctor")]
+    Doctor(Doctor),
     #[clap(name = "new")]
     New(New),
+    #[cfg(feature = "node")]
     #[clap(subcommand)]
     Node(Node),
+    #[clap(subcommand)]
+    Payment(Payment),
     #[clap(name = "run")]
     Run(Run),
     #[clap(subcommand)]
@@ -74,8 +95,12 @@ impl Command {
             Self::Account(command) => command.parse(),
             Self::Build(command) => command.parse(),
             Self::Clean(command) => command.parse(),
+            Self::Completions(command) => command.parse(),
+            Self::Doctor(command) => command.parse(),
             Self::New(command) => command.parse(),
+            #[cfg(feature = "node")]
             Self::Node(command) => command.parse(),
+            Self::Payment(command) => command.parse(),
             Self::Run(command) => command.parse(),
             Self::Update(command) => command.parse(),
         }
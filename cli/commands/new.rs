@@ -14,33 +14,63 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::Network;
+use crate::{
+    errors::CliError,
+    helpers::{is_program_id_available, remember_program_id},
+    Network,
+};
 use snarkvm::{package::Package, prelude::ProgramID};
 
-use anyhow::Result;
+use anyhow::{ensure, Result};
 use clap::Parser;
 use colored::Colorize;
-use core::str::FromStr;
+use core::{str::FromStr, time::Duration};
 
 /// Create a new Aleo package.
 #[derive(Debug, Parser)]
 pub struct New {
     /// The program name.
     name: String,
+    /// The node endpoint to check that the program ID is not taken, such as `127.0.0.1:4180`.
+    #[clap(long)]
+    endpoint: Option<String>,
 }
 
 impl New {
+    /// The timeout of the availability check.
+    const NETWORK_TIMEOUT: Duration = Duration::from_secs(3);
+    /// The program names reserved by the network.
+    const RESERVED_NAMES: &'static [&'static str] = &["aleo", "credits", "stake", "system"];
+
     /// Creates an Aleo package with the specified name.
     pub fn parse(self) -> Result<String> {
-        // Derive the program directory path.
-        let mut path = std::env::current_dir()?;
-        path.push(&self.name);
+        // Ensure the program name is not reserved.
+        ensure!(
+            !Self::RESERVED_NAMES.contains(&self.name.to_lowercase().as_str()),
+            CliError::User(format!("The program name '{}' is reserved", self.name))
+        );
 
         // Create the program ID from the name.
         let id = ProgramID::<Network>::from_str(&format!("{}.aleo", self.name))?;
 
+        // Ensure the program ID is not taken on the network of the given node.
+        if let Some(endpoint) = &self.endpoint {
+            let available = is_program_id_available(endpoint, &id, Self::NETWORK_TIMEOUT).map_err(|error| {
+                CliError::Network(format!("Failed to check the program ID at {endpoint} ({error})"))
+            })?;
+            ensure!(
+                available,
+                CliError::User(format!("The program ID '{id}' is already taken"))
+            );
+        }
+
+        // Derive the program directory path.
+        let mut path = std::env::current_dir()?;
+        path.push(&self.name);
+
         // Create the package.
         Package::create(&path, &id)?;
+        remember_program_id(&id.to_string());
 
         // Prepare the path string.
         let path_string = format!("(in \"{}\")", path.display());
@@ -14,108 +14,384 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{helpers::Ledger, Network};
-use snarkvm::{file::Manifest, package::Package};
+use crate::{
+    errors::CliError,
+    helpers::{
+        notify,
+        parse_address,
+        remember_program_id,
+        systemd_unit,
+        Credits,
+        DustPolicy,
+        Ledger,
+        NodeSettings,
+        Role,
+        ServerConfig,
+        SupportedNetwork,
+        TlsConfig,
+    },
+};
+use snarkvm::{
+    file::Manifest,
+    package::Package,
+    prelude::{Address, Network, PrivateKey, ProgramID, Testnet3},
+};
 
 use anyhow::{ensure, Result};
 use clap::Parser;
 use colored::*;
-use std::sync::Arc;
+use core::str::FromStr;
+use rand::SeedableRng;
+use rand_chacha::ChaChaRng;
+use std::{convert::TryFrom, net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
 
 /// Commands to operate a local development node.
 #[derive(Debug, Parser)]
 pub enum Node {
     /// Starts a local development node
-    Start {
-        /// Skips deploying the local program at genesis.
-        nodeploy: bool,
+    Start(Start),
+    /// Generates a systemd unit file that starts a local development node in this directory
+    InstallService {
+        /// Writes the unit file to the given path, instead of printing it.
+        #[clap(long, parse(from_os_str))]
+        output: Option<PathBuf>,
+        /// The arguments to start the node with, given after `--`.
+        #[clap(last = true)]
+        args: Vec<String>,
     },
 }
 
 impl Node {
     pub fn parse(self) -> Result<String> {
         match self {
-            Self::Start { nodeploy } => {
+            Self::Start(command) => command.parse(),
+            Self::InstallService { output, args } => {
                 // Derive the program directory path.
                 let directory = std::env::current_dir()?;
-
-                // Ensure the directory path exists.
-                ensure!(
-                    directory.exists(),
-                    "The program directory does not exist: {}",
-                    directory.display()
-                );
                 // Ensure the manifest file exists.
                 ensure!(
-                    Manifest::<Network>::exists_at(&directory),
-                    "Please start a local node in an Aleo program directory (missing '{}' at '{}')",
-                    Manifest::<Network>::file_name(),
-                    directory.display()
+                    Manifest::<Testnet3>::exists_at(&directory),
+                    CliError::User(format!(
+                        "Please install the service in an Aleo program directory (missing '{}' at '{}')",
+                        Manifest::<Testnet3>::file_name(),
+                        directory.display()
+                    ))
                 );
 
-                // Open the manifest file.
-                let manifest = Manifest::open(&directory)?;
-
-                println!(
-                    "⏳ Starting a local development node for '{}' (in-memory)...\n",
-                    manifest.program_id().to_string().bold()
-                );
+                // Prepare the command to start the node.
+                let mut command = vec![
+                    std::env::current_exe()?.display().to_string(),
+                    "node".to_string(),
+                    "start".to_string(),
+                ];
+                command.extend(args);
+                let unit = systemd_unit("Aleo local development node", &directory, &command);
 
-                // Retrieve the private key.
-                let private_key = manifest.development_private_key();
-
-                // Initialize the ledger.
-                let ledger = Arc::new(Ledger::<Network>::load(private_key)?);
-
-                // Deploy the local program.
-                if !nodeploy {
-                    println!(
-                        "\n📦 Deploying '{}' to the local development node...\n",
-                        manifest.program_id().to_string().bold()
-                    );
-
-                    // Load the package.
-                    let package = Package::open(&directory)?;
-                    // Load the program.
-                    let program = package.program();
-
-                    // Create a deployment transaction.
-                    let transaction = ledger.create_deploy(program, 1)?;
-                    // Add the transaction to the memory pool.
-                    ledger.add_to_memory_pool(transaction.clone())?;
-
-                    // Advance to the next block.
-                    let next_block = ledger.advance_to_next_block()?;
-                    println!(
-                        "\n🛡️  Produced block {} ({})\n\n{}\n",
-                        next_block.height(),
-                        next_block.hash(),
-                        serde_json::to_string_pretty(&next_block.header())?.dimmed()
-                    );
-
-                    println!(
-                        "✅ Deployed '{}' in transaction '{}'\n",
-                        manifest.program_id().to_string().bold(),
-                        transaction.id()
-                    );
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, unit)?;
+                        Ok(format!(
+                            "✅ Wrote the service unit file {}",
+                            format!("(in \"{}\")", path.display()).dimmed()
+                        ))
+                    }
+                    None => Ok(unit),
                 }
+            }
+        }
+    }
+}
+
+/// Starts a local development node.
+#[derive(Debug, Parser)]
+pub struct Start {
+    /// The network to run [options: testnet3].
+    #[clap(long, default_value = "testnet3")]
+    network: SupportedNetwork,
+    /// Skips deploying the local program at genesis.
+    nodeploy: bool,
+    /// Only returns record commitments from the records endpoints.
+    #[clap(long)]
+    redact_records: bool,
+    /// Grants a role to an API key, as `<KEY>=<ROLE>` (roles: read-only, broadcast, admin).
+    #[clap(long = "api-key", parse(try_from_str = ServerConfig::parse_api_key))]
+    api_keys: Vec<(String, Role)>,
+    /// Allows reading the ledger without an API key.
+    #[clap(long)]
+    public_reads: bool,
+    /// Loads additional API keys from a file, reloading it whenever it changes.
+    #[clap(long, parse(from_os_str))]
+    api_keys_file: Option<PathBuf>,
+    /// Writes the startup report to a file once the server is ready.
+    #[clap(long, parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    /// Allows injecting latency and faults into the requests via the admin endpoints.
+    #[clap(long)]
+    fault_injection: bool,
+    /// The window in which a rebroadcast transaction is rejected as a replay, in seconds.
+    #[clap(long, default_value = "60")]
+    replay_window: u64,
+    /// The number of incoming transactions to verify concurrently.
+    #[clap(long, default_value = "4")]
+    verification_threads: usize,
+    /// The maximum size of a transaction body, in bytes.
+    #[clap(long, default_value = "10485760")]
+    max_body_size: u64,
+    /// The number of requests per second allowed from each IP address; zero disables rate limiting.
+    #[clap(long, default_value = "0")]
+    rate_limit: f64,
+    /// The number of requests an IP address may burst above the rate limit [default: one second worth].
+    #[clap(long, default_value = "0")]
+    rate_limit_burst: u32,
+    /// The IP address to bind the server to.
+    #[clap(long, default_value = "0.0.0.0")]
+    addr: IpAddr,
+    /// The port to bind the server to, where zero picks a free port.
+    #[clap(long, default_value = "4180")]
+    port: u16,
+    /// The PEM-encoded certificate chain to serve HTTPS with.
+    #[clap(long, parse(from_os_str), requires = "tls-key")]
+    tls_cert: Option<PathBuf>,
+    /// The PEM-encoded private key to serve HTTPS with.
+    #[clap(long, parse(from_os_str), requires = "tls-cert")]
+    tls_key: Option<PathBuf>,
+    /// The OTLP collector to export traces to, such as `http://localhost:4317`.
+    #[clap(long)]
+    otlp_endpoint: Option<String>,
+    /// The address or saved account name to attribute produced blocks to [default: the node account].
+    #[clap(long)]
+    producer: Option<String>,
+    /// Claims the vested amounts of the node account's streams of the given program after every block.
+    #[clap(long = "auto-claim")]
+    auto_claim: Vec<String>,
+    /// The minimum interval between produced blocks, in milliseconds [default: 0, or 1000 with --dev].
+    #[clap(long)]
+    block_interval: Option<u64>,
+    /// Runs a local devnet, which produces blocks at an interval and pre-funds test accounts.
+    #[clap(long)]
+    dev: bool,
+    /// The number of test accounts to pre-fund in devnet mode.
+    #[clap(long, default_value = "4", requires = "dev")]
+    dev_accounts: u64,
+    /// The amount to pre-fund each test account with in devnet mode.
+    #[clap(long, default_value = "100 credits", requires = "dev")]
+    dev_funding: Credits,
+}
+
+impl Start {
+    /// The default interval between produced blocks in devnet mode, in milliseconds.
+    const DEV_BLOCK_INTERVAL_MS: u64 = 1_000;
+
+    pub fn parse(self) -> Result<String> {
+        match self.network {
+            SupportedNetwork::Testnet3 => self.start::<Testnet3>(),
+        }
+    }
+
+    /// Starts the node on the given network.
+    fn start<N: Network>(self) -> Result<String> {
+        let Self {
+            network: _,
+            nodeploy,
+            redact_records,
+            api_keys,
+            public_reads,
+            api_keys_file,
+            ready_file,
+            fault_injection,
+            replay_window,
+            verification_threads,
+            max_body_size,
+            rate_limit,
+            rate_limit_burst,
+            addr,
+            port,
+            tls_cert,
+            tls_key,
+            otlp_endpoint,
+            producer,
+            auto_claim,
+            block_interval,
+            dev,
+            dev_accounts,
+            dev_funding,
+        } = self;
+
+        // Derive the program directory path.
+        let directory = std::env::current_dir()?;
+
+        // Ensure the directory path exists.
+        ensure!(
+            directory.exists(),
+            CliError::User(format!("The program directory does not exist: {}", directory.display()))
+        );
+        // Ensure the manifest file exists.
+        ensure!(
+            Manifest::<N>::exists_at(&directory),
+            CliError::User(format!(
+                "Please start a local node in an Aleo program directory (missing '{}' at '{}')",
+                Manifest::<N>::file_name(),
+                directory.display()
+            ))
+        );
+
+        // Open the manifest file.
+        let manifest = Manifest::open(&directory)?;
+        remember_program_id(&manifest.program_id().to_string());
 
-                loop {
-                    // Create a transfer transaction.
-                    let transaction = ledger.create_transfer(ledger.address(), 1)?;
-                    // Add the transaction to the memory pool.
-                    ledger.add_to_memory_pool(transaction)?;
-
-                    // Advance to the next block.
-                    let next_block = ledger.advance_to_next_block()?;
-                    println!(
-                        "\n🛡️  Produced block {} ({})\n\n{}\n",
-                        next_block.height(),
-                        next_block.hash(),
-                        serde_json::to_string_pretty(&next_block.header())?.dimmed()
-                    );
+        println!(
+            "⏳ Starting a local development node for '{}' (in-memory)...\n",
+            manifest.program_id().to_string().bold()
+        );
+
+        // Retrieve the private key.
+        let private_key = manifest.development_private_key();
+
+        // Initialize the ledger.
+        let server_config = ServerConfig {
+            redact_records,
+            api_keys: api_keys.into_iter().collect(),
+            public_reads,
+            api_keys_file,
+            ready_file,
+            fault_injection,
+            replay_window: Duration::from_secs(replay_window),
+            verification_threads,
+            max_body_size,
+            rate_limit,
+            rate_limit_burst,
+            addr: Some(addr),
+            port: Some(port),
+            tls: tls_cert.zip(tls_key).map(|(cert, key)| TlsConfig { cert, key }),
+            otlp_endpoint,
+        };
+        let ledger = Arc::new(Ledger::<N>::load(private_key, server_config)?);
+        let block_interval = match (block_interval, dev) {
+            (Some(block_interval), _) => block_interval,
+            (None, true) => Self::DEV_BLOCK_INTERVAL_MS,
+            (None, false) => 0,
+        };
+        ledger.set_settings(NodeSettings {
+            block_interval_ms: block_interval,
+            ..Default::default()
+        });
+        if let Some(producer) = producer {
+            let producer = parse_address::<N>(&producer)
+                .map_err(|error| CliError::User(format!("Invalid producer address '{producer}': {error}")))?;
+            ledger.set_producer(producer);
+        }
+        let auto_claim = auto_claim
+            .iter()
+            .map(|program_id| {
+                ProgramID::<N>::from_str(program_id)
+                    .map_err(|error| CliError::User(format!("Invalid program ID '{program_id}': {error}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Deploy the local program.
+        if !nodeploy {
+            println!(
+                "\n📦 Deploying '{}' to the local development node...\n",
+                manifest.program_id().to_string().bold()
+            );
+
+            // Load the package.
+            let package = Package::open(&directory)?;
+            // Load the program.
+            let program = package.program();
+
+            // Create a deployment transaction.
+            let transaction = ledger.create_deploy(program, 1)?;
+            // Add the transaction to the memory pool.
+            ledger
+                .add_to_memory_pool(transaction.clone())
+                .map_err(|error| CliError::Rejected(error.to_string()))?;
+
+            // Advance to the next block.
+            let next_block = ledger.advance_to_next_block()?;
+            println!(
+                "\n🛡️  Produced block {} ({})\n\n{}\n",
+                next_block.height(),
+                next_block.hash(),
+                serde_json::to_string_pretty(&next_block.header())?.dimmed()
+            );
+
+            println!(
+                "✅ Deployed '{}' in transaction '{}'\n",
+                manifest.program_id().to_string().bold(),
+                transaction.id()
+            );
+        }
+
+        // Pre-fund the test accounts.
+        if dev {
+            println!("\n💰 Pre-funding {dev_accounts} test accounts with {dev_funding} each...\n");
+            for index in 0..dev_accounts {
+                // Derive the test account deterministically, so it is stable across runs.
+                let private_key = PrivateKey::<N>::new(&mut ChaChaRng::seed_from_u64(index))?;
+                let address = Address::try_from(&private_key)?;
+                // Fund the test account in its own block, so each transfer spends a fresh record.
+                let transaction =
+                    ledger.create_transfer(&address, dev_funding.microcredits(), DustPolicy::default())?;
+                ledger.add_to_memory_pool(transaction)?;
+                ledger.advance_to_next_block()?;
+
+                println!(" {:>12}  {}", "Private Key".cyan().bold(), private_key);
+                println!(" {:>12}  {}\n", "Address".cyan().bold(), address);
+            }
+        }
+
+        while !ledger.is_shutting_down() {
+            // Wait for the block interval, which may be reconfigured at runtime.
+            ledger.sleep_unless_shutting_down(Duration::from_millis(ledger.settings().block_interval_ms));
+            // Do not produce another block once the node is shutting down.
+            if ledger.is_shutting_down() {
+                break;
+            }
+
+            // Create a transfer transaction.
+            let transaction = ledger.create_transfer(ledger.address(), 1, DustPolicy::default())?;
+            // Add the transaction to the memory pool.
+            ledger.add_to_memory_pool(transaction)?;
+
+            // Advance to the next block.
+            let next_block = ledger.advance_to_next_block()?;
+
+            // Claim the vested amounts, to be included in the next block.
+            for program_id in &auto_claim {
+                let claims = ledger.create_vesting_claims(program_id).and_then(|claims| {
+                    claims
+                        .into_iter()
+                        .try_for_each(|claim| ledger.add_to_memory_pool(claim))
+                });
+                if let Err(error) = claims {
+                    eprintln!("Failed to claim the vested amounts of '{program_id}': {error}");
                 }
             }
+
+            if ledger.settings().log_level < 2 {
+                continue;
+            }
+            println!(
+                "\n🛡️  Produced block {} ({})\n\n{}\n",
+                next_block.height(),
+                next_block.hash(),
+                serde_json::to_string_pretty(&next_block.header())?.dimmed()
+            );
         }
+
+        // Notify the service manager that the node is stopping. The ledger and its memory pool are
+        // in memory only, so there is no write-ahead log to flush, and the pending transactions are dropped.
+        notify("STOPPING=1")?;
+        // Let the in-flight requests finish.
+        ledger.shut_down_server();
+        // Flush the pending traces.
+        #[cfg(feature = "telemetry")]
+        crate::helpers::stop_telemetry();
+
+        Ok(format!(
+            "✅ Stopped the local development node for '{}'",
+            manifest.program_id().to_string().bold()
+        ))
     }
 }
@@ -0,0 +1,172 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+    errors::CliError,
+    helpers::{http_request, parse_private_key, Credits, PaymentReceipt, PaymentRequest, SupportedNetwork},
+    Network,
+};
+use snarkvm::prelude::Network as _;
+
+use anyhow::{bail, Result};
+use clap::Parser;
+use colored::*;
+use core::{str::FromStr, time::Duration};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Commands to create, inspect, pay and verify payment requests.
+#[derive(Debug, Parser)]
+pub enum Payment {
+    /// Creates a payment request to the account of the given private key, signed by it
+    Request {
+        /// The private key or saved account name of the recipient, which signs the request.
+        #[clap(long)]
+        private_key: String,
+        /// The requested amount, such as `1.5 credits` or `1500000 microcredits`.
+        #[clap(long)]
+        amount: Credits,
+        /// The number of seconds after which the request expires.
+        #[clap(long, default_value = "3600")]
+        expires_in: u64,
+        /// The reference of the payment, such as an invoice number.
+        #[clap(long, default_value = "")]
+        reference: String,
+    },
+    /// Verifies the signature of a payment request, and prints its terms
+    Inspect {
+        /// The payment request, as printed by `aleo payment request`.
+        request: String,
+    },
+    /// Pays a payment request from the account of the node at the given endpoint
+    Pay {
+        /// The payment request, as printed by `aleo payment request`.
+        request: String,
+        /// The endpoint of the paying node, such as `127.0.0.1:4180`.
+        #[clap(long, default_value = "127.0.0.1:4180")]
+        endpoint: String,
+    },
+    /// Checks whether a payment request to the account of the node at the given endpoint was paid
+    Verify {
+        /// The payment request, as printed by `aleo payment request`.
+        request: String,
+        /// The endpoint of the recipient's node, such as `127.0.0.1:4180`.
+        #[clap(long, default_value = "127.0.0.1:4180")]
+        endpoint: String,
+    },
+}
+
+impl Payment {
+    /// The timeout of the requests to the node, which proves the payment.
+    const NODE_TIMEOUT: Duration = Duration::from_secs(300);
+
+    pub fn parse(self) -> Result<String> {
+        match self {
+            Self::Request {
+                private_key,
+                amount,
+                expires_in,
+                reference,
+            } => {
+                let private_key = parse_private_key::<Network>(&private_key)
+                    .map_err(|error| CliError::User(format!("Invalid private key: {error}")))?;
+                let issued_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+                let expiry = issued_at.saturating_add(expires_in as i64);
+                let request = PaymentRequest::new(
+                    &private_key,
+                    amount.microcredits(),
+                    issued_at,
+                    expiry,
+                    reference,
+                    &mut rand::thread_rng(),
+                )
+                .map_err(|error| CliError::User(error.to_string()))?;
+
+                Ok(request.to_string())
+            }
+            Self::Inspect { request } => {
+                let request = Self::parse_request(&request)?;
+
+                // Print the terms of the payment request.
+                let mut output = format!(" {:>10}  {}\n", "Recipient".cyan().bold(), request.recipient);
+                output += &format!(
+                    " {:>10}  {}\n",
+                    "Amount".cyan().bold(),
+                    Credits::from_microcredits(request.amount)
+                );
+                output += &format!(
+                    " {:>10}  {}\n",
+                    "To Pay".cyan().bold(),
+                    Credits::from_microcredits(request.payment_amount())
+                );
+                output += &format!(" {:>10}  {}\n", "Issued At".cyan().bold(), request.issued_at);
+                output += &format!(" {:>10}  {}\n", "Expiry".cyan().bold(), request.expiry);
+                output += &format!(" {:>10}  {}", "Reference".cyan().bold(), request.reference);
+
+                Ok(output)
+            }
+            Self::Pay { request, endpoint } => {
+                let request = Self::parse_request(&request)?;
+
+                // Ask the node to transfer the payment amount from its account.
+                let body = Self::post(&endpoint, "fulfill", &request)?;
+                let transaction_id: String = serde_json::from_str(&body)?;
+
+                Ok(format!(
+                    "✅ Paid {} to {} in transaction {transaction_id}",
+                    Credits::from_microcredits(request.payment_amount()),
+                    request.recipient
+                ))
+            }
+            Self::Verify { request, endpoint } => {
+                let request = Self::parse_request(&request)?;
+
+                // Ask the node to find the payment among the records of its account.
+                let body = Self::post(&endpoint, "verify", &request)?;
+                match serde_json::from_str::<Option<PaymentReceipt<Network>>>(&body)? {
+                    Some(receipt) => Ok(format!(
+                        "✅ The payment request was paid in block {} (record {})",
+                        receipt.block_height, receipt.commitment
+                    )),
+                    None => bail!(CliError::User("The payment request was not paid".to_string())),
+                }
+            }
+        }
+    }
+
+    /// Parses the given payment request, and ensures it is signed by its recipient.
+    fn parse_request(request: &str) -> Result<PaymentRequest<Network>> {
+        PaymentRequest::<Network>::from_str(request)
+            .map_err(|error| CliError::User(format!("Invalid payment request: {error}")).into())
+    }
+
+    /// Posts the given payment request to the given payment endpoint of the node, and returns the response body.
+    fn post(endpoint: &str, action: &str, request: &PaymentRequest<Network>) -> Result<String> {
+        let network = SupportedNetwork::from_id(Network::ID)?.name();
+        http_request(
+            endpoint,
+            "POST",
+            &format!("/{network}/payment/{action}"),
+            Some(&request.to_string()),
+            Self::NODE_TIMEOUT,
+        )
+        .map_err(|error| {
+            CliError::Network(format!(
+                "Failed to {action} the payment request at {endpoint} ({error})"
+            ))
+            .into()
+        })
+    }
+}
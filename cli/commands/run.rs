@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::{commands::Build, Aleo, Network};
+use crate::{commands::Build, errors::CliError, helpers::remember_program_id, Aleo, Network};
 use snarkvm::{
     package::Package,
     prelude::{Identifier, Locator, Value},
@@ -54,9 +54,10 @@ impl Run {
         if package.is_build_required::<Aleo>() {
             Build::build(&package, self.endpoint, self.offline)?;
         }
+        remember_program_id(&package.program_id().to_string());
         // Check that the function exists.
         if !package.program_file().program().contains_function(&self.function) {
-            bail!("Function '{}' does not exist.", self.function)
+            bail!(CliError::User(format!("Function '{}' does not exist.", self.function)))
         }
 
         // Prepare the locator.
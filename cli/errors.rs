@@ -14,6 +14,50 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
+/// The exit codes of the CLI.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    /// The command succeeded.
+    Success = 0,
+    /// The command failed for an unclassified reason.
+    Failure = 1,
+    /// The command failed due to invalid user input or environment.
+    UserError = 2,
+    /// The command failed to reach a remote endpoint.
+    NetworkError = 3,
+    /// The command was rejected by the ledger.
+    Rejected = 4,
+}
+
+impl ExitCode {
+    /// Returns the exit code for the given error.
+    pub fn from_error(error: &anyhow::Error) -> Self {
+        if let Some(error) = error.downcast_ref::<CliError>() {
+            return match error {
+                CliError::User(..) => Self::UserError,
+                CliError::Network(..) => Self::NetworkError,
+                CliError::Rejected(..) => Self::Rejected,
+            };
+        }
+        match error.is::<UpdaterError>() {
+            true => Self::NetworkError,
+            false => Self::Failure,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CliError {
+    #[error("{}", _0)]
+    User(String),
+
+    #[error("{}", _0)]
+    Network(String),
+
+    #[error("{}", _0)]
+    Rejected(String),
+}
+
 #[derive(Debug, Error)]
 pub enum UpdaterError {
     #[error("{}: {}", _0, _1)]
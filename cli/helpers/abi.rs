@@ -0,0 +1,140 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Network, Program};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// A structured description of a program's interface, to generate forms without parsing Aleo instructions.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProgramAbi {
+    /// The program ID.
+    pub program_id: String,
+    /// The functions of the program.
+    pub functions: Vec<FunctionAbi>,
+}
+
+/// A structured description of a program function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionAbi {
+    /// The function name.
+    pub name: String,
+    /// The inputs of the function.
+    pub inputs: Vec<ParameterAbi>,
+    /// The outputs of the function.
+    pub outputs: Vec<ParameterAbi>,
+}
+
+/// A structured description of a function input or output.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParameterAbi {
+    /// The type, such as `u64`, or the record name if the visibility is `record`.
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// The visibility.
+    pub visibility: Visibility,
+}
+
+/// The visibility of a function input or output.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Constant,
+    Public,
+    Private,
+    Record,
+}
+
+impl ProgramAbi {
+    /// Describes the interface of the given program.
+    ///
+    /// This snarkVM version has no finalize blocks, so functions only have inputs and outputs.
+    pub fn from_program<N: Network>(program: &Program<N>) -> Result<Self> {
+        let functions = program
+            .functions()
+            .values()
+            .map(|function| {
+                Ok(FunctionAbi {
+                    name: function.name().to_string(),
+                    inputs: function
+                        .inputs()
+                        .iter()
+                        .map(|input| ParameterAbi::parse(&input.value_type().to_string()))
+                        .collect::<Result<_>>()?,
+                    outputs: function
+                        .outputs()
+                        .iter()
+                        .map(|output| ParameterAbi::parse(&output.value_type().to_string()))
+                        .collect::<Result<_>>()?,
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            program_id: program.id().to_string(),
+            functions,
+        })
+    }
+}
+
+impl ParameterAbi {
+    /// Parses a value type, such as `u64.private` or `token.record`.
+    fn parse(value_type: &str) -> Result<Self> {
+        let (type_, visibility) = value_type
+            .rsplit_once('.')
+            .ok_or_else(|| anyhow!("Invalid value type '{value_type}'"))?;
+        let visibility = match visibility {
+            "constant" => Visibility::Constant,
+            "public" => Visibility::Public,
+            "private" => Visibility::Private,
+            "record" => Visibility::Record,
+            _ => return Err(anyhow!("Invalid visibility in value type '{value_type}'")),
+        };
+        Ok(Self {
+            type_: type_.to_string(),
+            visibility,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameter_parse() {
+        let parameter = ParameterAbi::parse("u64.private").unwrap();
+        assert_eq!("u64", parameter.type_);
+        assert_eq!(Visibility::Private, parameter.visibility);
+
+        let parameter = ParameterAbi::parse("token.record").unwrap();
+        assert_eq!("token", parameter.type_);
+        assert_eq!(Visibility::Record, parameter.visibility);
+
+        assert_eq!(
+            Visibility::Constant,
+            ParameterAbi::parse("field.constant").unwrap().visibility
+        );
+        assert_eq!(
+            Visibility::Public,
+            ParameterAbi::parse("address.public").unwrap().visibility
+        );
+
+        assert!(ParameterAbi::parse("u64").is_err());
+        assert!(ParameterAbi::parse("u64.secret").is_err());
+    }
+}
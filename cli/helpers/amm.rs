@@ -0,0 +1,168 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::Ledger;
+use snarkvm::prelude::{Identifier, Network, ProgramID, Transaction, Value};
+
+use anyhow::{ensure, Result};
+use core::str::FromStr;
+
+/// The reserves of a constant-product AMM pool.
+///
+/// A conforming program holds the reserves of a pair of tokens, charges a fee on the input amount,
+/// and exposes the following function, which must fail if the output is below the minimum:
+///   - `swap(amount_in: u64, min_amount_out: u64)`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Reserves {
+    /// The reserve of the input token.
+    pub reserve_in: u64,
+    /// The reserve of the output token.
+    pub reserve_out: u64,
+    /// The fee charged on the input amount, in basis points.
+    pub fee_bps: u16,
+}
+
+/// A quote for a swap, with its slippage protection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// The input amount.
+    pub amount_in: u64,
+    /// The expected output amount, at the quoted reserves.
+    pub amount_out: u64,
+    /// The minimum output amount, within the slippage tolerance.
+    pub min_amount_out: u64,
+}
+
+impl Reserves {
+    /// The number of basis points in one.
+    pub const BASIS_POINTS: u64 = 10_000;
+    /// The name of the swap function.
+    const SWAP: &'static str = "swap";
+
+    /// Returns the output amount of a swap of the given input amount, after fees.
+    pub fn amount_out(&self, amount_in: u64) -> Result<u64> {
+        ensure!(amount_in > 0, "The input amount must be positive");
+        ensure!(self.reserve_in > 0 && self.reserve_out > 0, "The pool has no liquidity");
+        ensure!(
+            u64::from(self.fee_bps) < Self::BASIS_POINTS,
+            "The fee of {} basis points is too high",
+            self.fee_bps
+        );
+        // Apply the constant-product formula to the input amount after fees.
+        let amount_in_after_fee = u128::from(amount_in) * u128::from(Self::BASIS_POINTS - u64::from(self.fee_bps));
+        let numerator = amount_in_after_fee * u128::from(self.reserve_out);
+        let denominator = u128::from(self.reserve_in) * u128::from(Self::BASIS_POINTS) + amount_in_after_fee;
+        // The quotient is below the output reserve, so it fits.
+        Ok((numerator / denominator) as u64)
+    }
+
+    /// Quotes a swap of the given input amount, with the given slippage tolerance in basis points.
+    pub fn quote(&self, amount_in: u64, slippage_bps: u16) -> Result<SwapQuote> {
+        ensure!(
+            u64::from(slippage_bps) <= Self::BASIS_POINTS,
+            "The slippage tolerance of {slippage_bps} basis points is too high"
+        );
+        let amount_out = self.amount_out(amount_in)?;
+        let min_amount_out = u128::from(amount_out) * u128::from(Self::BASIS_POINTS - u64::from(slippage_bps))
+            / u128::from(Self::BASIS_POINTS);
+        Ok(SwapQuote {
+            amount_in,
+            amount_out,
+            min_amount_out: min_amount_out as u64,
+        })
+    }
+}
+
+impl SwapQuote {
+    /// Ensures the swap still meets the minimum output amount at the given current reserves,
+    /// so a stale quote is aborted before it is broadcast.
+    pub fn ensure_within_tolerance(&self, current: &Reserves) -> Result<()> {
+        let amount_out = current.amount_out(self.amount_in)?;
+        ensure!(
+            amount_out >= self.min_amount_out,
+            "The quote moved beyond the slippage tolerance: expected at least {}, but would receive {amount_out}",
+            self.min_amount_out
+        );
+        Ok(())
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Creates a transaction swapping on the given AMM program, which fails on-chain if the output
+    /// falls below the minimum of the quote. The quote is checked against the current reserves first.
+    pub fn create_swap(
+        &self,
+        program_id: &ProgramID<N>,
+        quote: &SwapQuote,
+        current: &Reserves,
+    ) -> Result<Transaction<N>> {
+        quote.ensure_within_tolerance(current)?;
+        self.create_execute(
+            program_id,
+            Identifier::from_str(Reserves::SWAP)?,
+            &[
+                Value::from_str(&format!("{}u64", quote.amount_in))?,
+                Value::from_str(&format!("{}u64", quote.min_amount_out))?,
+            ],
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swap_quote() {
+        let reserves = Reserves {
+            reserve_in: 1_000_000,
+            reserve_out: 2_000_000,
+            fee_bps: 30,
+        };
+
+        // The output follows the constant-product formula after fees.
+        assert_eq!(1_992, reserves.amount_out(1_000).unwrap());
+        let quote = reserves.quote(1_000, 100).unwrap();
+        assert_eq!(1_992, quote.amount_out);
+        assert_eq!(1_972, quote.min_amount_out);
+
+        // The quote holds at the same reserves, and within the tolerance.
+        quote.ensure_within_tolerance(&reserves).unwrap();
+        let moved = Reserves {
+            reserve_out: 1_990_000,
+            ..reserves
+        };
+        quote.ensure_within_tolerance(&moved).unwrap();
+
+        // The quote is aborted beyond the tolerance.
+        let moved = Reserves {
+            reserve_out: 1_900_000,
+            ..reserves
+        };
+        assert!(quote.ensure_within_tolerance(&moved).is_err());
+
+        // Invalid swaps are rejected.
+        assert!(reserves.amount_out(0).is_err());
+        assert!(Reserves {
+            reserve_in: 0,
+            ..reserves
+        }
+        .amount_out(1)
+        .is_err());
+        assert!(reserves.quote(1_000, 10_001).is_err());
+    }
+}
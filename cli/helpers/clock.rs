@@ -0,0 +1,93 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use core::time::Duration;
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// A source of time for the node, so time-dependent behavior can be tested deterministically.
+pub trait Clock: Send + Sync {
+    /// Returns the current time, as the duration since the UNIX epoch.
+    fn now(&self) -> Duration;
+
+    /// Waits until the given duration has elapsed.
+    fn sleep(&self, duration: Duration);
+}
+
+/// A clock backed by the system wall-clock.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A clock that only moves when it is advanced, or when it is slept on.
+#[derive(Debug, Default)]
+pub struct MockClock {
+    /// The current time, in milliseconds since the UNIX epoch.
+    now_ms: AtomicU64,
+}
+
+impl MockClock {
+    /// Initializes a new mock clock at the given time since the UNIX epoch.
+    pub fn new(now: Duration) -> Self {
+        Self {
+            now_ms: AtomicU64::new(now.as_millis() as u64),
+        }
+    }
+
+    /// Advances the clock by the given duration.
+    pub fn advance(&self, duration: Duration) {
+        self.now_ms.fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.now_ms.load(Ordering::SeqCst))
+    }
+
+    /// Advances the clock by the given duration, without waiting.
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock() {
+        let clock = MockClock::new(Duration::from_secs(1_000));
+        assert_eq!(Duration::from_secs(1_000), clock.now());
+
+        clock.advance(Duration::from_millis(1_500));
+        assert_eq!(Duration::from_millis(1_001_500), clock.now());
+
+        clock.sleep(Duration::from_secs(60));
+        assert_eq!(Duration::from_millis(1_061_500), clock.now());
+    }
+}
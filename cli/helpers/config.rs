@@ -0,0 +1,228 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Address, Network, PrivateKey};
+
+use anyhow::{anyhow, ensure, Result};
+use core::str::FromStr;
+use indexmap::IndexSet;
+use std::{
+    convert::TryFrom,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+/// Returns the Aleo directory of the user, `~/.aleo`, if the home directory is known.
+pub fn aleo_directory() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".aleo"))
+}
+
+/// The configuration of the user in the Aleo directory, which holds the saved accounts
+/// and the recently used program IDs.
+#[derive(Clone, Debug)]
+pub struct UserConfig {
+    /// The Aleo directory.
+    directory: PathBuf,
+}
+
+impl UserConfig {
+    /// The maximum number of recently used program IDs that are kept.
+    const MAX_RECENT_PROGRAM_IDS: usize = 20;
+
+    /// Opens the configuration in the Aleo directory of the user.
+    pub fn open() -> Result<Self> {
+        let directory = aleo_directory().ok_or_else(|| anyhow!("The home directory is unknown"))?;
+        Ok(Self::at(directory))
+    }
+
+    /// Opens the configuration in the given directory.
+    pub fn at(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+
+    /// Returns the path of the file listing the recently used program IDs.
+    fn recent_program_ids_path(&self) -> PathBuf {
+        self.directory.join("recent_programs")
+    }
+
+    /// Returns the path of the directory containing the saved accounts.
+    fn accounts_path(&self) -> PathBuf {
+        self.directory.join("accounts")
+    }
+
+    /// Returns the recently used program IDs, the most recent first.
+    pub fn recent_program_ids(&self) -> Vec<String> {
+        fs::read_to_string(self.recent_program_ids_path())
+            .unwrap_or_default()
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect()
+    }
+
+    /// Records the given program ID as the most recently used one.
+    pub fn add_recent_program_id(&self, program_id: &str) -> Result<()> {
+        let mut program_ids = IndexSet::new();
+        program_ids.insert(program_id.to_string());
+        program_ids.extend(self.recent_program_ids());
+        let program_ids = program_ids
+            .into_iter()
+            .take(Self::MAX_RECENT_PROGRAM_IDS)
+            .collect::<Vec<_>>();
+
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.recent_program_ids_path(), program_ids.join("\n") + "\n")?;
+        Ok(())
+    }
+
+    /// Returns the names of the saved accounts, in alphabetical order.
+    pub fn account_names(&self) -> Vec<String> {
+        let mut names = fs::read_dir(self.accounts_path())
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .filter(|name| Self::is_valid_account_name(name))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// Saves the given private key under the given account name, which must not be taken.
+    pub fn save_account<N: Network>(&self, name: &str, private_key: &PrivateKey<N>) -> Result<()> {
+        ensure!(
+            Self::is_valid_account_name(name),
+            "Invalid account name '{name}', expected letters, digits, '-' and '_'"
+        );
+        fs::create_dir_all(self.accounts_path())?;
+
+        // Create the file, readable only by the user, as it holds the private key.
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options
+            .open(self.accounts_path().join(name))
+            .map_err(|error| anyhow!("Failed to save the account '{name}': {error}"))?;
+        writeln!(file, "{private_key}")?;
+        Ok(())
+    }
+
+    /// Returns the private key of the saved account with the given name.
+    pub fn load_account<N: Network>(&self, name: &str) -> Result<PrivateKey<N>> {
+        ensure!(Self::is_valid_account_name(name), "Invalid account name '{name}'");
+        let private_key = fs::read_to_string(self.accounts_path().join(name))
+            .map_err(|_| anyhow!("There is no saved account named '{name}'"))?;
+        PrivateKey::from_str(private_key.trim())
+    }
+
+    /// Returns `true` if the given account name is non-empty, and only contains letters, digits, '-' and '_'.
+    fn is_valid_account_name(name: &str) -> bool {
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+}
+
+/// Parses the given private key, or loads the saved account with the given name.
+pub fn parse_private_key<N: Network>(private_key: &str) -> Result<PrivateKey<N>> {
+    match PrivateKey::from_str(private_key) {
+        Ok(private_key) => Ok(private_key),
+        Err(error) => UserConfig::open()
+            .and_then(|config| config.load_account(private_key))
+            .map_err(|_| anyhow!("{error}")),
+    }
+}
+
+/// Parses the given address, or derives the address of the saved account with the given name.
+pub fn parse_address<N: Network>(address: &str) -> Result<Address<N>> {
+    match Address::from_str(address) {
+        Ok(address) => Ok(address),
+        Err(error) => UserConfig::open()
+            .and_then(|config| config.load_account::<N>(address))
+            .and_then(|private_key| Address::try_from(&private_key))
+            .map_err(|_| anyhow!("{error}")),
+    }
+}
+
+/// Records the given program ID as recently used, for the shell completions.
+///
+/// Failing to record the program ID does not fail the command that used it, so the error is ignored.
+pub fn remember_program_id(program_id: &str) {
+    let _ = UserConfig::open().and_then(|config| config.add_recent_program_id(program_id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_private_key;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    /// Returns a configuration in a new temporary directory.
+    fn sample_config() -> UserConfig {
+        let directory = std::env::temp_dir().join(format!("aleo-config-{}", rand::random::<u64>()));
+        UserConfig::at(directory)
+    }
+
+    #[test]
+    fn test_recent_program_ids() {
+        let config = sample_config();
+        assert!(config.recent_program_ids().is_empty());
+
+        // The most recently used program ID comes first, without duplicates.
+        config.add_recent_program_id("a.aleo").unwrap();
+        config.add_recent_program_id("b.aleo").unwrap();
+        config.add_recent_program_id("a.aleo").unwrap();
+        assert_eq!(vec!["a.aleo", "b.aleo"], config.recent_program_ids());
+
+        // Only the most recent program IDs are kept.
+        for index in 0..UserConfig::MAX_RECENT_PROGRAM_IDS {
+            config.add_recent_program_id(&format!("p{index}.aleo")).unwrap();
+        }
+        let program_ids = config.recent_program_ids();
+        assert_eq!(UserConfig::MAX_RECENT_PROGRAM_IDS, program_ids.len());
+        assert_eq!(
+            format!("p{}.aleo", UserConfig::MAX_RECENT_PROGRAM_IDS - 1),
+            program_ids[0]
+        );
+
+        fs::remove_dir_all(&config.directory).unwrap();
+    }
+
+    #[test]
+    fn test_accounts() {
+        let rng = &mut rand::thread_rng();
+        let config = sample_config();
+        assert!(config.account_names().is_empty());
+
+        // Save and load an account.
+        let private_key = sample_private_key::<CurrentNetwork, _>(rng).unwrap();
+        config.save_account("alice", &private_key).unwrap();
+        assert_eq!(private_key, config.load_account::<CurrentNetwork>("alice").unwrap());
+        assert_eq!(vec!["alice"], config.account_names());
+
+        // Saved accounts are not overwritten, and invalid or unknown names are rejected.
+        assert!(config.save_account("alice", &private_key).is_err());
+        assert!(config.save_account("../alice", &private_key).is_err());
+        assert!(config.load_account::<CurrentNetwork>("bob").is_err());
+
+        fs::remove_dir_all(&config.directory).unwrap();
+    }
+}
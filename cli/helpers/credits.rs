@@ -0,0 +1,202 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{bail, ensure, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// An amount of Aleo credits, stored in microcredits.
+///
+/// The CLI takes and prints amounts as credits. The server API, the library functions and the
+/// program inputs keep plain `u64` microcredits, so their formats stay stable.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Credits(u64);
+
+impl Credits {
+    /// The number of decimal places in one credit.
+    pub const DECIMALS: u32 = 6;
+    /// The number of microcredits in one credit.
+    pub const MICROCREDITS_PER_CREDIT: u64 = 10u64.pow(Self::DECIMALS);
+
+    /// Initializes a new amount from the given number of microcredits.
+    pub const fn from_microcredits(microcredits: u64) -> Self {
+        Self(microcredits)
+    }
+
+    /// Initializes a new amount from the given number of whole credits.
+    pub fn from_credits(credits: u64) -> Result<Self> {
+        match credits.checked_mul(Self::MICROCREDITS_PER_CREDIT) {
+            Some(microcredits) => Ok(Self(microcredits)),
+            None => bail!("The amount of {credits} credits overflows"),
+        }
+    }
+
+    /// Returns the amount in microcredits.
+    pub const fn microcredits(&self) -> u64 {
+        self.0
+    }
+
+    /// Returns the amount as a `u64` literal of microcredits, as expected by program inputs.
+    pub fn to_literal(&self) -> String {
+        format!("{}u64", self.0)
+    }
+
+    /// Parses a decimal amount of credits, such as `1.5`.
+    fn parse_credits(amount: &str) -> Result<Self> {
+        // Split the amount into the whole and fractional parts.
+        let (whole, fraction) = match amount.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (amount, ""),
+        };
+        ensure!(
+            !whole.is_empty() || !fraction.is_empty(),
+            "The amount '{amount}' is empty"
+        );
+        ensure!(
+            fraction.len() <= Self::DECIMALS as usize,
+            "The amount '{amount}' has more than {} decimal places",
+            Self::DECIMALS
+        );
+        ensure!(
+            whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()),
+            "The amount '{amount}' is not a valid number"
+        );
+
+        // Parse the whole credits.
+        let whole = match whole.is_empty() {
+            true => Self(0),
+            false => Self::from_credits(whole.parse()?)?,
+        };
+        // Parse the fractional credits, padded to microcredits.
+        let fraction = match fraction.is_empty() {
+            true => 0,
+            false => format!("{fraction:0<width$}", width = Self::DECIMALS as usize).parse::<u64>()?,
+        };
+
+        match whole.0.checked_add(fraction) {
+            Some(microcredits) => Ok(Self(microcredits)),
+            None => bail!("The amount '{amount}' overflows"),
+        }
+    }
+
+    /// Parses an integer amount of microcredits, such as `1500000` or `1500000u64`.
+    fn parse_microcredits(amount: &str) -> Result<Self> {
+        let amount = amount.strip_suffix("u64").unwrap_or(amount);
+        ensure!(
+            !amount.is_empty() && amount.chars().all(|c| c.is_ascii_digit()),
+            "The amount '{amount}' is not a valid number of microcredits"
+        );
+        match amount.parse() {
+            Ok(microcredits) => Ok(Self(microcredits)),
+            Err(_) => bail!("The amount '{amount}' overflows"),
+        }
+    }
+}
+
+impl FromStr for Credits {
+    type Err = Error;
+
+    /// Parses an amount such as `1.5 credits`, `1.5`, `1500000 microcredits` or `1500000u64`.
+    ///
+    /// Amounts without a unit are read as credits, unless they carry a `u64` suffix,
+    /// in which case they are read as microcredits.
+    fn from_str(amount: &str) -> Result<Self> {
+        let amount = amount.trim();
+        // Split the amount into the number and unit.
+        let (number, unit) = match amount.split_once(char::is_whitespace) {
+            Some((number, unit)) => (number, unit.trim()),
+            None => (amount, ""),
+        };
+
+        match unit {
+            "credits" | "credit" => Self::parse_credits(number),
+            "microcredits" | "microcredit" => Self::parse_microcredits(number),
+            "" => match number.ends_with("u64") {
+                true => Self::parse_microcredits(number),
+                false => Self::parse_credits(number),
+            },
+            _ => bail!("The unit '{unit}' is not supported, use 'credits' or 'microcredits'"),
+        }
+    }
+}
+
+impl fmt::Display for Credits {
+    /// Prints the amount in credits, always using `.` as the decimal separator.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let whole = self.0 / Self::MICROCREDITS_PER_CREDIT;
+        let fraction = self.0 % Self::MICROCREDITS_PER_CREDIT;
+        match fraction {
+            0 if whole == 1 => write!(f, "1 credit"),
+            0 => write!(f, "{whole} credits"),
+            _ => {
+                let fraction = format!("{fraction:0>width$}", width = Self::DECIMALS as usize);
+                write!(f, "{whole}.{} credits", fraction.trim_end_matches('0'))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_credits() {
+        assert_eq!(Credits::from_microcredits(1_500_000), "1.5 credits".parse().unwrap());
+        assert_eq!(Credits::from_microcredits(1_500_000), "1.5".parse().unwrap());
+        assert_eq!(Credits::from_microcredits(1_000_000), "1 credit".parse().unwrap());
+        assert_eq!(Credits::from_microcredits(500_000), ".5".parse().unwrap());
+        assert_eq!(Credits::from_microcredits(1), "0.000001 credits".parse().unwrap());
+    }
+
+    #[test]
+    fn test_parse_microcredits() {
+        assert_eq!(
+            Credits::from_microcredits(1_500_000),
+            "1500000u64 microcredits".parse().unwrap()
+        );
+        assert_eq!(
+            Credits::from_microcredits(1_500_000),
+            "1500000 microcredits".parse().unwrap()
+        );
+        assert_eq!(Credits::from_microcredits(1_500_000), "1500000u64".parse().unwrap());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("".parse::<Credits>().is_err());
+        assert!(".".parse::<Credits>().is_err());
+        assert!("-1 credits".parse::<Credits>().is_err());
+        assert!("1,5 credits".parse::<Credits>().is_err());
+        assert!("0.0000001 credits".parse::<Credits>().is_err());
+        assert!("1.5 microcredits".parse::<Credits>().is_err());
+        assert!("1 gates".parse::<Credits>().is_err());
+        assert!("18446744073710 credits".parse::<Credits>().is_err());
+        assert!("18446744073709551616u64".parse::<Credits>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("1.5 credits", Credits::from_microcredits(1_500_000).to_string());
+        assert_eq!("2 credits", Credits::from_microcredits(2_000_000).to_string());
+        assert_eq!("1 credit", Credits::from_microcredits(1_000_000).to_string());
+        assert_eq!("0.000001 credits", Credits::from_microcredits(1).to_string());
+        assert_eq!("1500000u64", Credits::from_microcredits(1_500_000).to_literal());
+
+        let amount = Credits::from_microcredits(u64::MAX);
+        assert_eq!(amount, amount.to_string().parse().unwrap());
+    }
+}
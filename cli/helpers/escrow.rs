@@ -0,0 +1,228 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{Ledger, RecordEntries};
+use snarkvm::prelude::{
+    Address,
+    Field,
+    Identifier,
+    Network,
+    Plaintext,
+    ProgramID,
+    Record,
+    RecordsFilter,
+    Transaction,
+    Value,
+    ViewKey,
+};
+
+use anyhow::{ensure, Result};
+use core::str::FromStr;
+use indexmap::IndexMap;
+
+/// An escrow record belonging to a conforming program.
+///
+/// A conforming program defines a record with `buyer`, `seller` and `arbiter` address entries,
+/// an `amount` u64 entry and a `funded` boolean entry, and exposes the following functions:
+///   - `create(seller: address, arbiter: address, amount: u64)`, which returns an unfunded escrow to the buyer
+///   - `fund(escrow: record)`, which locks the amount and returns a funded escrow to the arbiter
+///   - `release(escrow: record)`, which pays the amount to the seller
+///   - `refund(escrow: record)`, which returns the amount to the buyer
+#[derive(Clone, Debug)]
+pub struct Escrow<N: Network> {
+    /// The program ID of the escrow program.
+    program_id: ProgramID<N>,
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The buyer, who funds the escrow.
+    buyer: Address<N>,
+    /// The seller, who is paid when the escrow is released.
+    seller: Address<N>,
+    /// The arbiter, who releases or refunds the escrow.
+    arbiter: Address<N>,
+    /// The escrowed amount.
+    amount: u64,
+    /// Whether the amount is locked in the escrow.
+    funded: bool,
+    /// The record.
+    record: Record<N, Plaintext<N>>,
+}
+
+impl<N: Network> Escrow<N> {
+    /// The name of the create function.
+    const CREATE: &'static str = "create";
+    /// The name of the fund function.
+    const FUND: &'static str = "fund";
+    /// The name of the refund function.
+    const REFUND: &'static str = "refund";
+    /// The name of the release function.
+    const RELEASE: &'static str = "release";
+
+    /// Parses an escrow from the given record, returning an error if it does not conform.
+    pub fn from_record(
+        program_id: ProgramID<N>,
+        commitment: Field<N>,
+        record: Record<N, Plaintext<N>>,
+    ) -> Result<Self> {
+        let entries = RecordEntries::new(commitment, &record);
+        Ok(Self {
+            program_id,
+            commitment,
+            buyer: Address::from_str(entries.get("buyer")?)?,
+            seller: Address::from_str(entries.get("seller")?)?,
+            arbiter: Address::from_str(entries.get("arbiter")?)?,
+            amount: entries.integer("amount", "u64")?,
+            funded: bool::from_str(entries.get("funded")?)?,
+            record,
+        })
+    }
+
+    /// Returns the program ID of the escrow program.
+    pub const fn program_id(&self) -> &ProgramID<N> {
+        &self.program_id
+    }
+
+    /// Returns the record commitment.
+    pub const fn commitment(&self) -> &Field<N> {
+        &self.commitment
+    }
+
+    /// Returns the buyer, who funds the escrow.
+    pub const fn buyer(&self) -> &Address<N> {
+        &self.buyer
+    }
+
+    /// Returns the seller, who is paid when the escrow is released.
+    pub const fn seller(&self) -> &Address<N> {
+        &self.seller
+    }
+
+    /// Returns the arbiter, who releases or refunds the escrow.
+    pub const fn arbiter(&self) -> &Address<N> {
+        &self.arbiter
+    }
+
+    /// Returns the escrowed amount.
+    pub const fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Returns `true` if the amount is locked in the escrow.
+    pub const fn is_funded(&self) -> bool {
+        self.funded
+    }
+
+    /// Returns the record.
+    pub const fn record(&self) -> &Record<N, Plaintext<N>> {
+        &self.record
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Returns the escrows of the given program that belong to the given view key.
+    pub fn find_escrows(
+        &self,
+        program_id: &ProgramID<N>,
+        view_key: &ViewKey<N>,
+        filter: RecordsFilter<N>,
+    ) -> Result<IndexMap<Field<N>, Escrow<N>>> {
+        let commitments = self.program_commitments(program_id);
+        // Fetch the records of the view key, and parse the ones produced by the escrow program,
+        // skipping the program's other records.
+        Ok(self
+            .ledger
+            .read()
+            .find_records(view_key, filter)
+            .filter(|(commitment, _)| commitments.contains_key(commitment))
+            .filter_map(|(commitment, record)| {
+                let escrow = Escrow::from_record(*program_id, commitment, record).ok()?;
+                Some((commitment, escrow))
+            })
+            .collect())
+    }
+
+    /// Creates a transaction opening an unfunded escrow of the given program, with this account as the buyer.
+    pub fn create_escrow(
+        &self,
+        program_id: &ProgramID<N>,
+        seller: &Address<N>,
+        arbiter: &Address<N>,
+        amount: u64,
+    ) -> Result<Transaction<N>> {
+        ensure!(amount > 0, "The escrowed amount must be positive");
+        ensure!(seller != arbiter, "The seller cannot be the arbiter of the escrow");
+        self.create_execute(
+            program_id,
+            Identifier::from_str(Escrow::<N>::CREATE)?,
+            &[
+                Value::from_str(&format!("{seller}"))?,
+                Value::from_str(&format!("{arbiter}"))?,
+                Value::from_str(&format!("{amount}u64"))?,
+            ],
+            None,
+        )
+    }
+
+    /// Creates a transaction funding the given escrow, which must be unfunded.
+    pub fn create_escrow_fund(&self, escrow: &Escrow<N>) -> Result<Transaction<N>> {
+        ensure!(!escrow.is_funded(), "Escrow {} is already funded", escrow.commitment());
+        ensure!(
+            escrow.buyer() == self.address(),
+            "Escrow {} can only be funded by its buyer",
+            escrow.commitment()
+        );
+        self.create_escrow_execute(escrow, Escrow::<N>::FUND)
+    }
+
+    /// Creates a transaction releasing the given escrow to its seller, which must be funded.
+    pub fn create_escrow_release(&self, escrow: &Escrow<N>) -> Result<Transaction<N>> {
+        ensure!(escrow.is_funded(), "Escrow {} is not funded", escrow.commitment());
+        ensure!(
+            escrow.arbiter() == self.address(),
+            "Escrow {} can only be released by its arbiter",
+            escrow.commitment()
+        );
+        self.create_escrow_execute(escrow, Escrow::<N>::RELEASE)
+    }
+
+    /// Creates a transaction refunding the given escrow to its buyer, which must be funded.
+    pub fn create_escrow_refund(&self, escrow: &Escrow<N>) -> Result<Transaction<N>> {
+        ensure!(escrow.is_funded(), "Escrow {} is not funded", escrow.commitment());
+        ensure!(
+            escrow.arbiter() == self.address(),
+            "Escrow {} can only be refunded by its arbiter",
+            escrow.commitment()
+        );
+        self.create_escrow_execute(escrow, Escrow::<N>::REFUND)
+    }
+
+    /// Creates a transaction calling the given function on the escrow record, which must be unspent.
+    fn create_escrow_execute(&self, escrow: &Escrow<N>, function_name: &str) -> Result<Transaction<N>> {
+        // Ensure the escrow record is still unspent, as the ledger state may have moved on.
+        ensure!(
+            self.is_unspent(escrow.commitment()),
+            "Escrow {} is already spent",
+            escrow.commitment()
+        );
+
+        self.create_execute(
+            escrow.program_id(),
+            Identifier::from_str(function_name)?,
+            &[Value::Record(escrow.record().clone())],
+            None,
+        )
+    }
+}
@@ -0,0 +1,364 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{Ledger, RecordEntries};
+use snarkvm::prelude::{
+    Address,
+    Field,
+    Identifier,
+    Network,
+    Plaintext,
+    ProgramID,
+    Record,
+    RecordsFilter,
+    Transaction,
+    Value,
+    ViewKey,
+};
+
+use anyhow::{ensure, Result};
+use core::str::FromStr;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+/// The function names of a conforming governance program.
+///
+/// A conforming program defines three records: a proposal with a `proposal_id` field entry and an
+/// `end_height` u32 entry, a voting token with an `amount` u64 entry, and a ballot with a `proposal_id`
+/// field entry, an `approve` boolean entry and a `power` u64 entry. It exposes the following functions:
+///   - `propose(proposal_id: field, end_height: u32)`, which returns a proposal to the proposer
+///   - `vote(token: record, proposer: address, proposal_id: field, approve: boolean)`, which consumes the
+///     token, and returns a ballot weighted by the token amount to the proposer
+///
+/// As `vote` consumes the token, the voting power of a token can only be cast once.
+pub struct Governance;
+
+impl Governance {
+    /// The name of the propose function.
+    const PROPOSE: &'static str = "propose";
+    /// The name of the vote function.
+    const VOTE: &'static str = "vote";
+}
+
+/// A proposal record belonging to a conforming governance program.
+#[derive(Clone, Debug)]
+pub struct Proposal<N: Network> {
+    /// The program ID of the governance program.
+    program_id: ProgramID<N>,
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The ID of the proposal.
+    proposal_id: Field<N>,
+    /// The last height at which votes are counted.
+    end_height: u32,
+}
+
+impl<N: Network> Proposal<N> {
+    /// Parses a proposal from the given record, returning an error if it does not conform.
+    pub fn from_record(
+        program_id: ProgramID<N>,
+        commitment: Field<N>,
+        record: &Record<N, Plaintext<N>>,
+    ) -> Result<Self> {
+        let entries = RecordEntries::new(commitment, record);
+        Ok(Self {
+            program_id,
+            commitment,
+            proposal_id: Field::from_str(entries.get("proposal_id")?)?,
+            end_height: entries.integer("end_height", "u32")? as u32,
+        })
+    }
+
+    /// Returns the program ID of the governance program.
+    pub const fn program_id(&self) -> &ProgramID<N> {
+        &self.program_id
+    }
+
+    /// Returns the record commitment.
+    pub const fn commitment(&self) -> &Field<N> {
+        &self.commitment
+    }
+
+    /// Returns the ID of the proposal.
+    pub const fn proposal_id(&self) -> &Field<N> {
+        &self.proposal_id
+    }
+
+    /// Returns the last height at which votes are counted.
+    pub const fn end_height(&self) -> u32 {
+        self.end_height
+    }
+}
+
+/// A ballot record belonging to a conforming governance program, as received by the proposer.
+#[derive(Clone, Debug)]
+pub struct Ballot<N: Network> {
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The ID of the proposal voted on.
+    proposal_id: Field<N>,
+    /// Whether the vote approves the proposal.
+    approve: bool,
+    /// The voting power of the vote.
+    power: u64,
+    /// The height of the block containing the vote.
+    height: u32,
+}
+
+impl<N: Network> Ballot<N> {
+    /// Parses a ballot from the given record, included in the block at the given height,
+    /// returning an error if it does not conform.
+    pub fn from_record(commitment: Field<N>, record: &Record<N, Plaintext<N>>, height: u32) -> Result<Self> {
+        let entries = RecordEntries::new(commitment, record);
+        Ok(Self {
+            commitment,
+            proposal_id: Field::from_str(entries.get("proposal_id")?)?,
+            approve: bool::from_str(entries.get("approve")?)?,
+            power: entries.integer("power", "u64")?,
+            height,
+        })
+    }
+
+    /// Returns the record commitment.
+    pub const fn commitment(&self) -> &Field<N> {
+        &self.commitment
+    }
+
+    /// Returns the ID of the proposal voted on.
+    pub const fn proposal_id(&self) -> &Field<N> {
+        &self.proposal_id
+    }
+
+    /// Returns `true` if the vote approves the proposal.
+    pub const fn approves(&self) -> bool {
+        self.approve
+    }
+
+    /// Returns the voting power of the vote.
+    pub const fn power(&self) -> u64 {
+        self.power
+    }
+
+    /// Returns the height of the block containing the vote.
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+/// The result of a vote on a proposal.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    /// The voting power in favour of the proposal.
+    pub approve: u64,
+    /// The voting power against the proposal.
+    pub reject: u64,
+    /// The number of counted ballots.
+    pub ballots: usize,
+}
+
+impl Tally {
+    /// Tallies the given `(approve, power, height)` votes, counting the ones cast up to the end height.
+    fn count(votes: impl IntoIterator<Item = (bool, u64, u32)>, end_height: u32) -> Self {
+        let mut tally = Self::default();
+        for (approve, power, _) in votes.into_iter().filter(|(_, _, height)| *height <= end_height) {
+            match approve {
+                true => tally.approve = tally.approve.saturating_add(power),
+                false => tally.reject = tally.reject.saturating_add(power),
+            }
+            tally.ballots += 1;
+        }
+        tally
+    }
+
+    /// Returns `true` if the proposal has more voting power in favour than against.
+    pub const fn is_approved(&self) -> bool {
+        self.approve > self.reject
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Returns the proposals of the given program that belong to the given view key.
+    pub fn find_proposals(
+        &self,
+        program_id: &ProgramID<N>,
+        view_key: &ViewKey<N>,
+        filter: RecordsFilter<N>,
+    ) -> Result<IndexMap<Field<N>, Proposal<N>>> {
+        let commitments = self.program_commitments(program_id);
+        // Skip the records of the program that are not proposals.
+        Ok(self
+            .ledger
+            .read()
+            .find_records(view_key, filter)
+            .filter(|(commitment, _)| commitments.contains_key(commitment))
+            .filter_map(|(commitment, record)| {
+                let proposal = Proposal::from_record(*program_id, commitment, &record).ok()?;
+                Some((commitment, proposal))
+            })
+            .collect())
+    }
+
+    /// Returns the unspent voting tokens of the given program that belong to the account, with their amounts.
+    fn find_voting_tokens(&self, program_id: &ProgramID<N>) -> Result<Vec<(Record<N, Plaintext<N>>, u64)>> {
+        let commitments = self.program_commitments(program_id);
+        // Skip the records of the program that are not voting tokens.
+        Ok(self
+            .ledger
+            .read()
+            .find_records(self.view_key(), self.unspent_filter())
+            .filter(|(commitment, _)| commitments.contains_key(commitment))
+            .filter_map(|(commitment, record)| {
+                let amount = RecordEntries::new(commitment, &record).integer("amount", "u64").ok()?;
+                Some((record, amount))
+            })
+            .collect())
+    }
+
+    /// Returns the voting power of the account in the given program, which is the total amount of its unspent voting tokens.
+    pub fn voting_power(&self, program_id: &ProgramID<N>) -> Result<u64> {
+        Ok(self
+            .find_voting_tokens(program_id)?
+            .iter()
+            .fold(0u64, |power, (_, amount)| power.saturating_add(*amount)))
+    }
+
+    /// Creates a transaction proposing the given proposal, which counts votes up to the given end height.
+    pub fn create_proposal(
+        &self,
+        program_id: &ProgramID<N>,
+        proposal_id: Field<N>,
+        end_height: u32,
+    ) -> Result<Transaction<N>> {
+        let latest_height = self.ledger.read().latest_height();
+        ensure!(
+            end_height > latest_height,
+            "The proposal would end at {end_height}, which is not after the latest height {latest_height}"
+        );
+
+        self.create_execute(
+            program_id,
+            Identifier::from_str(Governance::PROPOSE)?,
+            &[
+                Value::from_str(&proposal_id.to_string())?,
+                Value::from_str(&format!("{end_height}u32"))?,
+            ],
+            None,
+        )
+    }
+
+    /// Creates the transactions voting on the given proposal with all of the account's unspent voting tokens,
+    /// sending the ballots to the proposer. The tokens are consumed by the votes.
+    pub fn create_votes(
+        &self,
+        program_id: &ProgramID<N>,
+        proposer: &Address<N>,
+        proposal_id: Field<N>,
+        approve: bool,
+    ) -> Result<Vec<Transaction<N>>> {
+        let tokens = self.find_voting_tokens(program_id)?;
+        ensure!(
+            !tokens.is_empty(),
+            "The Aleo account has no voting power in '{program_id}'"
+        );
+
+        tokens
+            .into_iter()
+            .map(|(token, _)| {
+                self.create_execute(
+                    program_id,
+                    Identifier::from_str(Governance::VOTE)?,
+                    &[
+                        Value::Record(token),
+                        Value::from_str(&proposer.to_string())?,
+                        Value::from_str(&proposal_id.to_string())?,
+                        Value::from_str(&approve.to_string())?,
+                    ],
+                    None,
+                )
+            })
+            .collect()
+    }
+
+    /// Tallies the votes on the given proposal of the account.
+    ///
+    /// Only the ballots produced by the governance program in blocks up to the end height of the
+    /// proposal are counted, whether they have been spent or not. As each ballot consumes its voting
+    /// token, the voting power of a token is counted at most once.
+    pub fn tally_votes(&self, proposal: &Proposal<N>) -> Result<Tally> {
+        let commitments = self.program_commitments(proposal.program_id());
+        // Skip the records of the program that are not ballots.
+        let ballots = self
+            .ledger
+            .read()
+            .find_records(self.view_key(), RecordsFilter::All)
+            .filter_map(|(commitment, record)| {
+                let height = *commitments.get(&commitment)?;
+                Ballot::from_record(commitment, &record, height).ok()
+            })
+            .filter(|ballot| ballot.proposal_id() == proposal.proposal_id())
+            .collect::<Vec<_>>();
+
+        Ok(Tally::count(
+            ballots
+                .iter()
+                .map(|ballot| (ballot.approves(), ballot.power(), ballot.height())),
+            proposal.end_height(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tally_count() {
+        // An empty vote is not approved.
+        let tally = Tally::count(vec![], 10);
+        assert_eq!(Tally::default(), tally);
+        assert!(!tally.is_approved());
+
+        // The votes are weighted by their power.
+        let tally = Tally::count(vec![(true, 30, 1), (false, 20, 2), (false, 5, 3)], 10);
+        assert_eq!(
+            Tally {
+                approve: 30,
+                reject: 25,
+                ballots: 3
+            },
+            tally
+        );
+        assert!(tally.is_approved());
+
+        // A tie is not approved.
+        assert!(!Tally::count(vec![(true, 10, 1), (false, 10, 1)], 10).is_approved());
+
+        // The votes cast after the end height are not counted.
+        let tally = Tally::count(vec![(true, 10, 10), (false, 100, 11)], 10);
+        assert_eq!(
+            Tally {
+                approve: 10,
+                reject: 0,
+                ballots: 1
+            },
+            tally
+        );
+
+        // Large voting powers do not overflow.
+        let tally = Tally::count(vec![(true, u64::MAX, 1), (true, 1, 1)], 10);
+        assert_eq!(u64::MAX, tally.approve);
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::SupportedNetwork;
+use snarkvm::prelude::{Network, ProgramID};
+
+use anyhow::{anyhow, ensure, Result};
+use core::time::Duration;
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+/// Sends a request with the given method, path and optional JSON body to the node at the given endpoint,
+/// such as `127.0.0.1:4180`, and returns the body of the response, or an error if the request did not succeed.
+pub fn http_request(endpoint: &str, method: &str, path: &str, body: Option<&str>, timeout: Duration) -> Result<String> {
+    let socket_addr = endpoint
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("the endpoint does not resolve"))?;
+    let mut stream = TcpStream::connect_timeout(&socket_addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+
+    // Send the request.
+    write!(
+        stream,
+        "{method} {path} HTTP/1.0\r\nHost: {endpoint}\r\nAccept: application/json\r\n"
+    )?;
+    match body {
+        Some(body) => write!(
+            stream,
+            "Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )?,
+        None => write!(stream, "\r\n")?,
+    }
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+
+    // Parse the response.
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or_else(|| anyhow!("the response is malformed"))?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    ensure!(
+        status == "200",
+        "the node responded with status {status} ({})",
+        body.trim()
+    );
+    Ok(body.to_string())
+}
+
+/// Returns `true` if the given program ID is not taken by a program deployed to the node at the given endpoint.
+pub fn is_program_id_available<N: Network>(
+    endpoint: &str,
+    program_id: &ProgramID<N>,
+    timeout: Duration,
+) -> Result<bool> {
+    let network = SupportedNetwork::from_id(N::ID)?.name();
+    let body = http_request(
+        endpoint,
+        "GET",
+        &format!("/{network}/program/{program_id}/available"),
+        None,
+        timeout,
+    )?;
+    Ok(serde_json::from_str(&body)?)
+}
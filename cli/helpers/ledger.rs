@@ -14,11 +14,26 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::helpers::Server;
+use crate::helpers::{
+    Clock,
+    Credits,
+    MemoryPool,
+    PoolEntry,
+    PoolEvictions,
+    PoolLimits,
+    PoolPosition,
+    RecordSelection,
+    RecordSelector,
+    SystemClock,
+};
+#[cfg(feature = "node")]
+use crate::helpers::{Server, ServerConfig};
 use snarkvm::prelude::{
     Address,
     Block,
     BlockMemory,
+    Ciphertext,
+    Entry,
     Field,
     Identifier,
     Network,
@@ -28,7 +43,9 @@ use snarkvm::prelude::{
     ProgramID,
     Record,
     RecordsFilter,
+    ToBytes,
     Transaction,
+    Transition,
     Value,
     ViewKey,
     Zero,
@@ -36,18 +53,314 @@ use snarkvm::prelude::{
 };
 
 use anyhow::{anyhow, ensure, Result};
-use core::str::FromStr;
-use indexmap::IndexMap;
+use core::{fmt, str::FromStr, time::Duration};
+use indexmap::{IndexMap, IndexSet};
+#[cfg(feature = "node")]
 use once_cell::race::OnceBox;
+use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
-use std::{convert::TryFrom, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    ops::Range,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::broadcast;
 
 pub(crate) type InternalLedger<N> = snarkvm::prelude::Ledger<N, BlockMemory<N>>;
 
+/// The policy for change records below a dust threshold (in gates).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DustPolicy {
+    /// Allows change records of any size.
+    Allow,
+    /// Rejects transfers that would leave a change record below the threshold.
+    Reject { threshold: u64 },
+}
+
+impl DustPolicy {
+    /// Returns the dust threshold (in gates).
+    pub const fn threshold(&self) -> u64 {
+        match self {
+            Self::Allow => 0,
+            Self::Reject { threshold } => *threshold,
+        }
+    }
+}
+
+impl Default for DustPolicy {
+    fn default() -> Self {
+        Self::Allow
+    }
+}
+
+/// The node settings that can be reconfigured at runtime.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NodeSettings {
+    /// The minimum interval between produced blocks, in milliseconds.
+    pub block_interval_ms: u64,
+    /// The log level [options: 0, 1, 2, 3].
+    pub log_level: u8,
+    /// The maximum number of broadcast transactions per block; zero means no limit.
+    pub max_block_transactions: usize,
+    /// The maximum number of pending broadcast transactions per sender; zero means no limit.
+    pub max_pending_per_sender: usize,
+    /// The maximum number of pending broadcast transactions; zero means no limit.
+    pub max_pending_transactions: usize,
+    /// The maximum total size of the pending broadcast transactions, in bytes; zero means no limit.
+    pub max_pending_bytes: usize,
+    /// The time after which a pending broadcast transaction is evicted, in seconds; zero means never.
+    pub pending_ttl_secs: u64,
+}
+
+impl Default for NodeSettings {
+    fn default() -> Self {
+        Self {
+            block_interval_ms: 0,
+            log_level: 2,
+            max_block_transactions: 0,
+            max_pending_per_sender: 0,
+            max_pending_transactions: 0,
+            max_pending_bytes: 0,
+            pending_ttl_secs: 0,
+        }
+    }
+}
+
+/// Returns the ownership matrix of the given record ciphertexts and view keys, where
+/// `matrix[i][j]` is `true` if the `i`-th record is owned by the `j`-th view key.
+///
+/// The address of each view key is derived once, rather than once per record.
+pub fn ownership_matrix<N: Network>(
+    ciphertexts: &[Record<N, Ciphertext<N>>],
+    view_keys: &[ViewKey<N>],
+) -> Result<Vec<Vec<bool>>> {
+    // Derive the addresses of the view keys.
+    let addresses = view_keys.iter().map(Address::try_from).collect::<Result<Vec<_>>>()?;
+    // Check the ownership of each record against each view key.
+    Ok(ciphertexts
+        .iter()
+        .map(|ciphertext| {
+            view_keys
+                .iter()
+                .zip(&addresses)
+                .map(|(view_key, address)| ciphertext.is_owner(address, view_key))
+                .collect()
+        })
+        .collect())
+}
+
+/// The block range and pagination filters of a records query.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RecordsPage {
+    /// The first block height to include records from.
+    pub start_height: Option<u32>,
+    /// The block height to stop including records at, exclusive.
+    pub end_height: Option<u32>,
+    /// The maximum number of records to return.
+    pub limit: Option<usize>,
+    /// The number of records to skip.
+    pub offset: usize,
+}
+
+/// A record as found on the ledger, with its origin.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct RecordOrigin<N: Network> {
+    /// The record commitment.
+    pub commitment: Field<N>,
+    /// The record ciphertext.
+    pub ciphertext: Record<N, Ciphertext<N>>,
+    /// The height of the block that created the record.
+    pub block_height: u32,
+    /// The ID of the transition that created the record.
+    pub transition_id: N::TransitionID,
+    /// The program of the transition that created the record.
+    pub program_id: ProgramID<N>,
+    /// Whether the record is spent, if its serial number was given.
+    pub spent: Option<bool>,
+}
+
+/// A record ciphertext as found on the ledger, for clients that scan for their records locally.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct RecordCiphertext<N: Network> {
+    /// The record commitment.
+    pub commitment: Field<N>,
+    /// The record ciphertext.
+    pub ciphertext: Record<N, Ciphertext<N>>,
+    /// The height of the block that created the record.
+    pub block_height: u32,
+}
+
+/// The transaction that spent a record, as found on the ledger.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct SpendingTransaction<N: Network> {
+    /// The serial number of the spent record.
+    pub serial_number: Field<N>,
+    /// The ID of the spending transaction.
+    pub transaction_id: N::TransactionID,
+    /// The ID of the spending transition.
+    pub transition_id: N::TransitionID,
+    /// The height of the block containing the transaction.
+    pub block_height: u32,
+}
+
+/// The blocks produced by an address, and the rewards credited for them.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ProducerRewards {
+    /// The number of blocks produced.
+    pub blocks: u32,
+    /// The rewards credited, in microcredits.
+    pub microcredits: u64,
+}
+
+/// The recent block production of the node, for operators to monitor it.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct ProducerStatus<N: Network> {
+    /// The address that produced blocks are attributed to.
+    pub producer: Address<N>,
+    /// The blocks produced by the producer, and the rewards credited for them.
+    pub rewards: ProducerRewards,
+    /// The heights of the recent blocks attributed to the producer.
+    pub recent_blocks: Vec<u32>,
+    /// The number of block intervals without a block during the recent blocks,
+    /// or `None` if the node does not produce blocks at an interval.
+    pub missed_slots: Option<u64>,
+}
+
+/// The credits supply of the ledger, in microcredits.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct Supply {
+    /// The supply minted in the genesis block.
+    pub genesis: u64,
+    /// The supply minted by transitions after the genesis block.
+    pub minted: u64,
+    /// The fees paid by transitions, which are burned.
+    pub burned: u64,
+    /// The rewards credited to block producers, which are bookkeeping only and not minted as records.
+    pub rewards: u64,
+    /// The total supply, which is the minted supply less the burned fees.
+    pub total: u64,
+    /// An estimate of the circulating supply, which excludes the node account's unspent records.
+    pub circulating: u64,
+    /// The current reward per block.
+    pub block_reward: u64,
+    /// The position in the block reward schedule, which is the number of blocks after the genesis block.
+    pub schedule_position: u32,
+}
+
+/// The status of a broadcast transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// The transaction is in a block.
+    Confirmed { block_height: u32 },
+    /// The transaction is waiting in the memory pool.
+    Pending,
+    /// The transaction was rejected.
+    Rejected { reason: String },
+    /// The transaction is not known to the node.
+    Unknown,
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Confirmed { block_height } => write!(f, "confirmed in block {block_height}"),
+            Self::Pending => write!(f, "pending"),
+            Self::Rejected { reason } => write!(f, "rejected ({reason})"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// The data entries of a record, by name.
+pub(crate) struct RecordEntries<N: Network> {
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The data entries.
+    entries: IndexMap<String, String>,
+}
+
+impl<N: Network> RecordEntries<N> {
+    /// Collects the data entries of the given record.
+    pub(crate) fn new(commitment: Field<N>, record: &Record<N, Plaintext<N>>) -> Self {
+        let entries = record
+            .data()
+            .iter()
+            .map(|(name, entry)| {
+                let value = match entry {
+                    Entry::Constant(plaintext) | Entry::Public(plaintext) | Entry::Private(plaintext) => plaintext,
+                };
+                (name.to_string(), value.to_string())
+            })
+            .collect();
+        Self { commitment, entries }
+    }
+
+    /// Retrieves the given entry.
+    pub(crate) fn get(&self, name: &str) -> Result<&str> {
+        self.entries
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| anyhow!("Record {} is missing a '{name}' entry", self.commitment))
+    }
+
+    /// Parses the given integer entry.
+    pub(crate) fn integer(&self, name: &str, suffix: &str) -> Result<u64> {
+        let value = self.get(name)?;
+        value
+            .strip_suffix(suffix)
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| anyhow!("Record {} has an invalid '{name}' entry: {value}", self.commitment))
+    }
+}
+
 pub struct Ledger<N: Network> {
     /// The internal ledger.
     pub ledger: RwLock<InternalLedger<N>>,
+    /// The node settings.
+    settings: RwLock<NodeSettings>,
+    /// A flag indicating the node is shutting down.
+    shutting_down: AtomicBool,
+    /// The clock.
+    clock: Arc<dyn Clock>,
+    /// The time the ledger was initialized.
+    started_at: Duration,
+    /// The broadcast transactions waiting to be selected into a block.
+    memory_pool: RwLock<MemoryPool<N::TransactionID, Transaction<N>>>,
+    /// The reasons the most recently rejected transactions were rejected.
+    rejections: RwLock<IndexMap<N::TransactionID, String>>,
+    /// The address that produced blocks are attributed to.
+    producer: RwLock<Address<N>>,
+    /// The blocks produced and rewards credited, by producer address.
+    rewards: RwLock<IndexMap<Address<N>, ProducerRewards>>,
+    /// The producer and production time of the most recently produced blocks, by height.
+    production: RwLock<IndexMap<u32, (Address<N>, Duration)>>,
+    /// The supply minted in the genesis block, in microcredits.
+    genesis_supply: OnceCell<u64>,
+    /// The microcredits minted and burned by the transition fees of the blocks after the genesis block.
+    emission: RwLock<(u64, u64)>,
+    /// The sender of newly added blocks.
+    blocks: broadcast::Sender<Block<N>>,
+    /// The height of the block containing each transaction, by transaction ID.
+    transaction_index: RwLock<IndexMap<N::TransactionID, u32>>,
+    /// The transaction containing each transition, by transition ID.
+    transition_index: RwLock<IndexMap<N::TransitionID, N::TransactionID>>,
+    /// The block height, transaction and transition that created each record, by commitment.
+    record_index: RwLock<IndexMap<Field<N>, (u32, N::TransactionID, N::TransitionID)>>,
+    /// The block height, transaction and transition that spent each record, by serial number.
+    serial_number_index: RwLock<IndexMap<Field<N>, (u32, N::TransactionID, N::TransitionID)>>,
+    /// The commitments produced by each program, with the height of the block containing them.
+    program_index: RwLock<IndexMap<ProgramID<N>, IndexMap<Field<N>, u32>>>,
     /// The server.
+    #[cfg(feature = "node")]
     server: OnceBox<Server<N>>,
     /// The account private key.
     private_key: PrivateKey<N>,
@@ -58,8 +371,37 @@ pub struct Ledger<N: Network> {
 }
 
 impl<N: Network> Ledger<N> {
-    /// Initializes a new instance of the ledger.
-    pub fn load(private_key: &PrivateKey<N>) -> Result<Arc<Self>> {
+    /// The reward credited to the producer of each block, in microcredits.
+    pub const BLOCK_REWARD: u64 = Credits::MICROCREDITS_PER_CREDIT;
+    /// The number of rejected transactions whose reasons are remembered.
+    const MAX_REJECTIONS: usize = 1024;
+    /// The number of produced blocks that are remembered for the producer status.
+    const RECENT_BLOCKS: usize = 100;
+    /// The longest step of a wait on the clock, which bounds how late a shutdown is noticed.
+    const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Initializes a new instance of the ledger, and starts its server.
+    #[cfg(feature = "node")]
+    pub fn load(private_key: &PrivateKey<N>, server_config: ServerConfig) -> Result<Arc<Self>> {
+        // Initialize the ledger.
+        let ledger = Self::new(private_key)?;
+        // Initialize the server.
+        let server = Server::<N>::start(ledger.clone(), server_config)?;
+        ledger
+            .server
+            .set(Box::new(server))
+            .map_err(|_| anyhow!("Failed to save the server"))?;
+        // Return the ledger.
+        Ok(ledger)
+    }
+
+    /// Initializes a new instance of the ledger, without a server.
+    pub fn new(private_key: &PrivateKey<N>) -> Result<Arc<Self>> {
+        Self::new_with_clock(private_key, Arc::new(SystemClock))
+    }
+
+    /// Initializes a new instance of the ledger with the given clock, without a server.
+    pub fn new_with_clock(private_key: &PrivateKey<N>, clock: Arc<dyn Clock>) -> Result<Arc<Self>> {
         // Derive the view key and address.
         let view_key = ViewKey::try_from(private_key)?;
         let address = Address::try_from(&view_key)?;
@@ -69,27 +411,231 @@ impl<N: Network> Ledger<N> {
         // Create a genesis block.
         let genesis = Block::genesis(&VM::new()?, private_key, rng)?;
         // Initialize the ledger.
-        let ledger = Arc::new(Self {
+        let ledger = Self {
             ledger: RwLock::new(InternalLedger::new_with_genesis(&genesis, address)?),
+            settings: Default::default(),
+            shutting_down: AtomicBool::new(false),
+            started_at: clock.now(),
+            clock,
+            memory_pool: Default::default(),
+            rejections: Default::default(),
+            producer: RwLock::new(address),
+            rewards: Default::default(),
+            production: Default::default(),
+            genesis_supply: OnceCell::new(),
+            emission: Default::default(),
+            blocks: broadcast::channel(16).0,
+            transaction_index: Default::default(),
+            transition_index: Default::default(),
+            record_index: Default::default(),
+            serial_number_index: Default::default(),
+            program_index: Default::default(),
+            #[cfg(feature = "node")]
             server: OnceBox::new(),
             private_key: private_key.clone(),
             view_key,
             address,
-        });
-        // Initialize the server.
-        let server = Server::<N>::start(ledger.clone())?;
-        ledger
-            .server
-            .set(Box::new(server))
-            .map_err(|_| anyhow!("Failed to save the server"))?;
-        // Return the ledger.
-        Ok(ledger)
+        };
+        // Index the genesis block.
+        ledger.index_block(&genesis);
+        Ok(Arc::new(ledger))
+    }
+
+    /// Adds the transactions, transitions, records and serial numbers of the given block to the lookup indexes,
+    /// the records to the commitments of their program, and the transition fees to the emission.
+    fn index_block(&self, block: &Block<N>) {
+        // A transition with a negative fee mints credits, and a positive fee is burned.
+        if block.height() > 0 {
+            let mut emission = self.emission.write();
+            for transition in block.transitions() {
+                let fee = *transition.fee();
+                match fee < 0 {
+                    true => emission.0 = emission.0.saturating_add(fee.unsigned_abs()),
+                    false => emission.1 = emission.1.saturating_add(fee.unsigned_abs()),
+                }
+            }
+        }
+
+        let mut transaction_index = self.transaction_index.write();
+        let mut transition_index = self.transition_index.write();
+        let mut record_index = self.record_index.write();
+        let mut serial_number_index = self.serial_number_index.write();
+        let mut program_index = self.program_index.write();
+        for (transaction_id, transaction) in block.transactions().iter() {
+            transaction_index.insert(*transaction_id, block.height());
+            for transition in transaction.transitions() {
+                transition_index.insert(*transition.id(), *transaction_id);
+                for commitment in transition.commitments() {
+                    record_index.insert(*commitment, (block.height(), *transaction_id, *transition.id()));
+                }
+                program_index
+                    .entry(*transition.program_id())
+                    .or_default()
+                    .extend(transition.commitments().map(|commitment| (*commitment, block.height())));
+                for serial_number in transition.serial_numbers() {
+                    serial_number_index.insert(*serial_number, (block.height(), *transaction_id, *transition.id()));
+                }
+            }
+        }
     }
 
     /// Returns the account address.
     pub const fn address(&self) -> &Address<N> {
         &self.address
     }
+
+    /// Returns the address that produced blocks are attributed to.
+    pub fn producer(&self) -> Address<N> {
+        *self.producer.read()
+    }
+
+    /// Sets the address that produced blocks are attributed to.
+    pub fn set_producer(&self, producer: Address<N>) {
+        *self.producer.write() = producer;
+    }
+
+    /// Returns the blocks produced by the given address, and the rewards credited for them.
+    pub fn rewards(&self, address: &Address<N>) -> ProducerRewards {
+        self.rewards.read().get(address).copied().unwrap_or_default()
+    }
+
+    /// Returns the time since the ledger was initialized.
+    pub fn uptime(&self) -> Duration {
+        self.clock.now().saturating_sub(self.started_at)
+    }
+
+    /// Returns the time the latest block was produced, or the time the ledger was initialized if there is none.
+    pub fn last_block_time(&self) -> Duration {
+        self.production
+            .read()
+            .values()
+            .last()
+            .map_or(self.started_at, |(_, time)| *time)
+    }
+
+    /// Returns the recent block production of the current producer.
+    pub fn producer_status(&self) -> ProducerStatus<N> {
+        let producer = self.producer();
+        let production = self.production.read();
+        let recent_blocks = production
+            .iter()
+            .filter(|(_, (address, _))| *address == producer)
+            .map(|(height, _)| *height)
+            .collect();
+        // Slots are only derivable if blocks are produced at an interval.
+        let interval = Duration::from_millis(self.settings().block_interval_ms);
+        let missed_slots = match interval.is_zero() {
+            true => None,
+            false => {
+                let times = production.values().map(|(_, time)| *time).collect::<Vec<_>>();
+                Some(missed_slots(&times, self.clock.now(), interval))
+            }
+        };
+        ProducerStatus {
+            producer,
+            rewards: self.rewards(&producer),
+            recent_blocks,
+            missed_slots,
+        }
+    }
+
+    /// Returns the credits supply of the ledger.
+    pub fn supply(&self) -> Result<Supply> {
+        // The genesis block mints its supply to the node account.
+        let genesis = *self.genesis_supply.get_or_try_init(|| -> Result<u64> {
+            let genesis = self.ledger.read().get_block(0)?;
+            Ok(self
+                .find_block_records(&genesis, &self.view_key)?
+                .values()
+                .map(|record| ***record.gates())
+                .sum())
+        })?;
+        let (minted, burned) = *self.emission.read();
+        let latest_height = self.ledger.read().latest_height();
+        let rewards = self
+            .rewards
+            .read()
+            .values()
+            .map(|rewards| rewards.microcredits)
+            .sum::<u64>();
+        // The rewards are not minted as records, so they are not part of the supply.
+        let total = genesis.saturating_add(minted).saturating_sub(burned);
+        // Estimate the circulating supply as everything outside the node account.
+        let unspent = self
+            .find_unspent_records()?
+            .values()
+            .map(|record| ***record.gates())
+            .sum::<u64>();
+        Ok(Supply {
+            genesis,
+            minted,
+            burned,
+            rewards,
+            total,
+            circulating: total.saturating_sub(unspent),
+            block_reward: Self::BLOCK_REWARD,
+            schedule_position: latest_height,
+        })
+    }
+
+    /// Returns the account view key.
+    pub const fn view_key(&self) -> &ViewKey<N> {
+        &self.view_key
+    }
+
+    /// Returns the clock.
+    pub fn clock(&self) -> &dyn Clock {
+        &*self.clock
+    }
+
+    /// Returns the node settings.
+    pub fn settings(&self) -> NodeSettings {
+        self.settings.read().clone()
+    }
+
+    /// Updates the node settings.
+    pub fn set_settings(&self, settings: NodeSettings) {
+        self.memory_pool.write().set_limits(PoolLimits {
+            per_sender: settings.max_pending_per_sender,
+            max_count: settings.max_pending_transactions,
+            max_bytes: settings.max_pending_bytes,
+            ttl: Duration::from_secs(settings.pending_ttl_secs),
+        });
+        *self.settings.write() = settings;
+    }
+
+    /// Returns a receiver of the blocks added to the ledger from now on.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<Block<N>> {
+        self.blocks.subscribe()
+    }
+
+    /// Signals the node to shut down.
+    pub fn shut_down(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+    }
+
+    /// Shuts down the server gracefully, if it was started.
+    #[cfg(feature = "node")]
+    pub fn shut_down_server(&self) {
+        if let Some(server) = self.server.get() {
+            server.shutdown();
+        }
+    }
+
+    /// Returns `true` if the node is shutting down.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the given duration on the clock, returning early if the node starts shutting down.
+    pub fn sleep_unless_shutting_down(&self, duration: Duration) {
+        let mut remaining = duration;
+        while !remaining.is_zero() && !self.is_shutting_down() {
+            let step = remaining.min(Self::SHUTDOWN_POLL_INTERVAL);
+            self.clock.sleep(step);
+            remaining -= step;
+        }
+    }
 }
 
 impl<N: Network> Ledger<N> {
@@ -98,21 +644,229 @@ impl<N: Network> Ledger<N> {
         self.ledger.write().add_to_memory_pool(transaction)
     }
 
+    /// Returns `true` if the given program is deployed.
+    pub fn contains_program(&self, program_id: &ProgramID<N>) -> bool {
+        self.ledger.read().vm().contains_program(program_id)
+    }
+
+    /// Returns the given program, if it is deployed.
+    pub fn find_program(&self, program_id: &ProgramID<N>) -> Result<Option<Program<N>>> {
+        // The credits program is part of the VM, and is not deployed in a block.
+        if program_id.to_string() == "credits.aleo" {
+            return Ok(Some(Program::credits()?));
+        }
+        if !self.contains_program(program_id) {
+            return Ok(None);
+        }
+        // Find the deployment of the program.
+        let ledger = self.ledger.read();
+        for height in 0..=ledger.latest_height() {
+            for (_, transaction) in ledger.get_block(height)?.transactions().iter() {
+                if let Transaction::Deploy(_, deployment, _) = transaction {
+                    if deployment.program_id() == program_id {
+                        return Ok(Some(deployment.program().clone()));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Returns `true` if the given transaction and its proofs are valid, without adding it to the ledger.
+    #[cfg_attr(feature = "telemetry", tracing::instrument(skip_all))]
+    pub fn verify_transaction(&self, transaction: &Transaction<N>) -> bool {
+        self.ledger.read().vm().verify(transaction)
+    }
+
+    /// Verifies the given broadcast transaction, and queues it in the memory pool by fee.
+    pub fn queue_transaction(&self, transaction: Transaction<N>, sender: &str) -> Result<()> {
+        let transaction_id = transaction.id();
+        let result = (|| {
+            // Verify the transaction.
+            ensure!(
+                self.verify_transaction(&transaction),
+                "Invalid transaction '{transaction_id}'"
+            );
+            // Queue the transaction.
+            let fee = u64::try_from(transaction.fee()?).unwrap_or(0);
+            let size = transaction.to_bytes_le()?.len();
+            let now = self.clock.now();
+            self.memory_pool
+                .write()
+                .insert(transaction_id, transaction, fee, sender, size, now)
+        })();
+        // Remember the rejection reason.
+        if let Err(error) = &result {
+            self.record_rejection(transaction_id, error.to_string());
+        }
+        self.record_memory_pool_usage();
+        result
+    }
+
+    /// Remembers the reason the given transaction was rejected.
+    fn record_rejection(&self, transaction_id: N::TransactionID, reason: String) {
+        metrics::increment_counter!("aleo_broadcast_failures_total");
+        let mut rejections = self.rejections.write();
+        rejections.insert(transaction_id, reason);
+        if rejections.len() > Self::MAX_REJECTIONS {
+            rejections.shift_remove_index(0);
+        }
+    }
+
+    /// Returns the state root after the block at the given height.
+    pub fn state_root(&self, height: u32) -> Result<Field<N>> {
+        let ledger = self.ledger.read();
+        let latest_height = ledger.latest_height();
+        ensure!(
+            height <= latest_height,
+            "Block {height} does not exist (latest is {latest_height})"
+        );
+        match height == latest_height {
+            true => Ok(*ledger.latest_state_root()),
+            // The next block commits to the state root that this block produced.
+            false => Ok(*ledger.get_block(height + 1)?.header().previous_state_root()),
+        }
+    }
+
+    /// Returns the status of the given transaction.
+    pub fn transaction_status(&self, transaction_id: &N::TransactionID) -> Result<TransactionStatus> {
+        // Check the memory pool.
+        if self.memory_pool.read().contains(transaction_id) {
+            return Ok(TransactionStatus::Pending);
+        }
+        // Check the blocks.
+        if let Some(block_height) = self.transaction_index.read().get(transaction_id) {
+            return Ok(TransactionStatus::Confirmed {
+                block_height: *block_height,
+            });
+        }
+        // Check the rejections.
+        Ok(match self.rejections.read().get(transaction_id) {
+            Some(reason) => TransactionStatus::Rejected { reason: reason.clone() },
+            None => TransactionStatus::Unknown,
+        })
+    }
+
+    /// Returns the queued broadcast transactions, in the order they are selected into blocks.
+    pub fn memory_pool_ordering(&self) -> Vec<PoolPosition<N::TransactionID>> {
+        self.memory_pool.read().ordering()
+    }
+
+    /// Returns the queued broadcast transactions, in the order they are selected into blocks.
+    pub fn memory_pool_transactions(&self) -> Vec<Transaction<N>> {
+        self.memory_pool.read().transactions().into_iter().cloned().collect()
+    }
+
+    /// Returns the given queued broadcast transaction.
+    pub fn memory_pool_transaction(&self, transaction_id: &N::TransactionID) -> Option<Transaction<N>> {
+        self.memory_pool.read().get(transaction_id).cloned()
+    }
+
+    /// Returns the number of broadcast transactions evicted from the memory pool.
+    pub fn memory_pool_evictions(&self) -> PoolEvictions {
+        self.memory_pool.read().evictions()
+    }
+
+    /// Returns the number of queued broadcast transactions and their total size, in bytes.
+    pub fn memory_pool_usage(&self) -> (usize, usize) {
+        let memory_pool = self.memory_pool.read();
+        (memory_pool.len(), memory_pool.size())
+    }
+
+    /// Reports the memory pool usage to the metrics recorder.
+    fn record_memory_pool_usage(&self) {
+        let (transactions, bytes) = self.memory_pool_usage();
+        metrics::gauge!("aleo_memory_pool_transactions", transactions as f64);
+        metrics::gauge!("aleo_memory_pool_bytes", bytes as f64);
+    }
+
+    /// Returns the number of block subscribers.
+    pub fn block_subscribers(&self) -> usize {
+        self.blocks.receiver_count()
+    }
+
     /// Advances the ledger to the next block.
+    #[cfg_attr(feature = "telemetry", tracing::instrument(skip_all))]
     pub fn advance_to_next_block(&self) -> Result<Block<N>> {
+        // Select the queued broadcast transactions into the block.
+        let limit = self.settings().max_block_transactions;
+        let entries = {
+            let mut memory_pool = self.memory_pool.write();
+            memory_pool.evict_expired(self.clock.now());
+            memory_pool.select_entries(limit)
+        };
+        let mut selected = Vec::with_capacity(entries.len());
+        for (transaction_id, entry) in entries {
+            // A transaction is still in the internal memory pool if the previous block failed.
+            if self.ledger.read().memory_pool().contains_key(&transaction_id) {
+                selected.push((transaction_id, entry));
+                continue;
+            }
+            match self.add_to_memory_pool(entry.transaction.clone()) {
+                Ok(()) => selected.push((transaction_id, entry)),
+                Err(error) => {
+                    self.record_rejection(transaction_id, error.to_string());
+                    // Log the error.
+                    if self.settings().log_level >= 1 {
+                        eprintln!("{error}");
+                    }
+                }
+            }
+        }
+
         // Initialize an RNG.
         let rng = &mut ::rand::thread_rng();
         // Propose the next block.
-        let next_block = self.ledger.read().propose_next_block(&self.private_key, rng)?;
+        let proposal = self.ledger.read().propose_next_block(&self.private_key, rng);
+        let next_block = match proposal {
+            Ok(next_block) => next_block,
+            Err(error) => {
+                self.restore_memory_pool(selected);
+                return Err(error);
+            }
+        };
         // Add the next block to the ledger.
-        if let Err(error) = self.ledger.write().add_next_block(&next_block) {
-            // Log the error.
-            eprintln!("{error}");
+        let result = self.ledger.write().add_next_block(&next_block);
+        match result {
+            Ok(()) => {
+                // Credit the producer.
+                let mut rewards = self.rewards.write();
+                let producer_rewards = rewards.entry(self.producer()).or_default();
+                producer_rewards.blocks += 1;
+                producer_rewards.microcredits = producer_rewards.microcredits.saturating_add(Self::BLOCK_REWARD);
+                drop(rewards);
+                // Remember the production of the block.
+                let mut production = self.production.write();
+                production.insert(next_block.height(), (self.producer(), self.clock.now()));
+                if production.len() > Self::RECENT_BLOCKS {
+                    production.shift_remove_index(0);
+                }
+                drop(production);
+                // Index the block.
+                self.index_block(&next_block);
+                metrics::gauge!("aleo_block_height", f64::from(next_block.height()));
+                // Notify the subscribers; an error only means there are none.
+                let _ = self.blocks.send(next_block.clone());
+            }
+            Err(error) => {
+                self.restore_memory_pool(selected);
+                // Log the error.
+                if self.settings().log_level >= 1 {
+                    eprintln!("{error}");
+                }
+            }
         }
+        self.record_memory_pool_usage();
         // Return the next block.
         Ok(next_block)
     }
 
+    /// Puts the transactions selected for a failed block back into the memory pool, to retry them in the next block.
+    fn restore_memory_pool(&self, selected: Vec<(N::TransactionID, PoolEntry<Transaction<N>>)>) {
+        self.memory_pool.write().restore(selected);
+        self.record_memory_pool_usage();
+    }
+
     /// Returns the unspent records.
     pub fn find_unspent_records(&self) -> Result<IndexMap<Field<N>, Record<N, Plaintext<N>>>> {
         // Fetch the unspent records.
@@ -126,7 +880,216 @@ impl<N: Network> Ledger<N> {
         Ok(records)
     }
 
+    /// Returns `true` if the given record belongs to the account and is unspent, including records without gates.
+    ///
+    /// This scans the records of the account, so callers checking several records should use `unspent_filter` instead.
+    pub fn is_unspent(&self, commitment: &Field<N>) -> bool {
+        self.ledger
+            .read()
+            .find_records(&self.view_key, self.unspent_filter())
+            .any(|(unspent, _)| unspent == *commitment)
+    }
+
+    /// Returns the filter for the unspent records of the account, including records without gates.
+    pub(crate) fn unspent_filter(&self) -> RecordsFilter<N> {
+        RecordsFilter::AllUnspent(self.private_key)
+    }
+
+    /// Returns the records of the given view key that were created in the given block.
+    ///
+    /// Only the records created in the block are trial-decrypted, so the cost does not grow with the ledger.
+    pub fn find_block_records(
+        &self,
+        block: &Block<N>,
+        view_key: &ViewKey<N>,
+    ) -> Result<IndexMap<Field<N>, Record<N, Plaintext<N>>>> {
+        let address = Address::try_from(view_key)?;
+        block
+            .transitions()
+            .flat_map(|transition| transition.output_records())
+            .filter(|(_, ciphertext)| ciphertext.is_owner(&address, view_key))
+            .map(|(commitment, ciphertext)| Ok((*commitment, ciphertext.decrypt(view_key)?)))
+            .collect()
+    }
+
+    /// Returns the record with the given commitment and its origin, if it exists.
+    /// If the serial number of the record is given, its spent status is included.
+    pub fn find_record(
+        &self,
+        commitment: &Field<N>,
+        serial_number: Option<&Field<N>>,
+    ) -> Result<Option<RecordOrigin<N>>> {
+        // Look up the transition that created the record.
+        let (block_height, transition_id) = match self.record_index.read().get(commitment) {
+            Some((block_height, _, transition_id)) => (*block_height, *transition_id),
+            None => return Ok(None),
+        };
+        let ledger = self.ledger.read();
+        let block = ledger.get_block(block_height)?;
+        let transition = match block.transitions().find(|transition| *transition.id() == transition_id) {
+            Some(transition) => transition,
+            None => return Ok(None),
+        };
+        let spent = serial_number
+            .map(|serial_number| ledger.contains_serial_number(serial_number))
+            .transpose()?;
+        Ok(transition
+            .output_records()
+            .find(|(c, _)| *c == commitment)
+            .map(|(_, ciphertext)| RecordOrigin {
+                commitment: *commitment,
+                ciphertext: ciphertext.clone(),
+                block_height,
+                transition_id,
+                program_id: *transition.program_id(),
+                spent,
+            }))
+    }
+
+    /// Returns the record ciphertexts that were created in the given block range, without decrypting them.
+    pub fn find_record_ciphertexts(&self, range: Range<u32>) -> Result<Vec<RecordCiphertext<N>>> {
+        let ledger = self.ledger.read();
+        let mut ciphertexts = Vec::new();
+        for height in range.start..range.end.min(ledger.latest_height().saturating_add(1)) {
+            let block = ledger.get_block(height)?;
+            for transition in block.transitions() {
+                ciphertexts.extend(
+                    transition
+                        .output_records()
+                        .map(|(commitment, ciphertext)| RecordCiphertext {
+                            commitment: *commitment,
+                            ciphertext: ciphertext.clone(),
+                            block_height: height,
+                        }),
+                );
+            }
+        }
+        Ok(ciphertexts)
+    }
+
+    /// Returns the commitments produced by the given program, with the height of the block containing them.
+    pub(crate) fn program_commitments(&self, program_id: &ProgramID<N>) -> IndexMap<Field<N>, u32> {
+        self.program_index.read().get(program_id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the confirmed transaction with the given ID, if it exists.
+    pub fn find_transaction(&self, transaction_id: &N::TransactionID) -> Result<Option<Transaction<N>>> {
+        // Look up the block containing the transaction.
+        let block_height = match self.transaction_index.read().get(transaction_id) {
+            Some(block_height) => *block_height,
+            None => return Ok(None),
+        };
+        let block = self.ledger.read().get_block(block_height)?;
+        Ok(block
+            .transactions()
+            .iter()
+            .find(|(id, _)| *id == transaction_id)
+            .map(|(_, transaction)| transaction.clone()))
+    }
+
+    /// Returns the confirmed transition with the given ID, if it exists.
+    pub fn find_transition(&self, transition_id: &N::TransitionID) -> Result<Option<Transition<N>>> {
+        // Look up the transaction containing the transition.
+        let transaction_id = match self.transition_index.read().get(transition_id) {
+            Some(transaction_id) => *transaction_id,
+            None => return Ok(None),
+        };
+        Ok(self.find_transaction(&transaction_id)?.and_then(|transaction| {
+            transaction
+                .transitions()
+                .find(|transition| transition.id() == transition_id)
+                .cloned()
+        }))
+    }
+
+    /// Returns the transaction that spent the record with the given serial number, if it exists.
+    pub fn find_spending_transaction(&self, serial_number: &Field<N>) -> Result<Option<SpendingTransaction<N>>> {
+        Ok(self
+            .serial_number_index
+            .read()
+            .get(serial_number)
+            .map(|(block_height, transaction_id, transition_id)| SpendingTransaction {
+                serial_number: *serial_number,
+                transaction_id: *transaction_id,
+                transition_id: *transition_id,
+                block_height: *block_height,
+            }))
+    }
+
+    /// Returns the page of records of the given view key that match the given filter.
+    pub fn find_records_page(
+        &self,
+        view_key: &ViewKey<N>,
+        filter: RecordsFilter<N>,
+        page: RecordsPage,
+    ) -> Result<IndexMap<Field<N>, Record<N, Plaintext<N>>>> {
+        let ledger = self.ledger.read();
+        // Collect the commitments produced in the block range, if one is given.
+        let commitments = match (page.start_height, page.end_height) {
+            (None, None) => None,
+            (start_height, end_height) => {
+                let end_height = end_height
+                    .unwrap_or(u32::MAX)
+                    .min(ledger.latest_height().saturating_add(1));
+                let mut commitments = IndexSet::new();
+                for height in start_height.unwrap_or(0)..end_height {
+                    let block = ledger.get_block(height)?;
+                    commitments.extend(
+                        block
+                            .transitions()
+                            .flat_map(|transition| transition.commitments().copied()),
+                    );
+                }
+                Some(commitments)
+            }
+        };
+        // Fetch the records of the view key, and keep the requested page.
+        Ok(ledger
+            .find_records(view_key, filter)
+            .filter(|(commitment, _)| {
+                commitments
+                    .as_ref()
+                    .map_or(true, |commitments| commitments.contains(commitment))
+            })
+            .skip(page.offset)
+            .take(page.limit.unwrap_or(usize::MAX))
+            .collect())
+    }
+
+    /// Returns the records of each of the given view keys that were created in the given block range.
+    ///
+    /// The blocks are read once for all of the view keys, and only the ownership check and decryption are repeated per key.
+    pub fn scan_many(
+        &self,
+        view_keys: &[ViewKey<N>],
+        range: Range<u32>,
+    ) -> Result<Vec<IndexMap<Field<N>, Record<N, Plaintext<N>>>>> {
+        // Collect the records produced in the block range.
+        let (commitments, ciphertexts): (Vec<_>, Vec<_>) = self
+            .find_record_ciphertexts(range)?
+            .into_iter()
+            .map(|record| (record.commitment, record.ciphertext))
+            .unzip();
+        // Check the ownership of each record against each view key.
+        let owners = ownership_matrix(&ciphertexts, view_keys)?;
+        // Decrypt the records of each view key.
+        view_keys
+            .iter()
+            .enumerate()
+            .map(|(index, view_key)| {
+                commitments
+                    .iter()
+                    .zip(&ciphertexts)
+                    .zip(&owners)
+                    .filter(|(_, owners)| owners[index])
+                    .map(|((commitment, ciphertext), _)| Ok((*commitment, ciphertext.decrypt(view_key)?)))
+                    .collect()
+            })
+            .collect()
+    }
+
     /// Creates a deploy transaction.
+    #[cfg_attr(feature = "telemetry", tracing::instrument(skip_all))]
     pub fn create_deploy(&self, program: &Program<N>, additional_fee: u64) -> Result<Transaction<N>> {
         // Fetch the unspent records.
         let records = self.find_unspent_records()?;
@@ -159,28 +1122,119 @@ impl<N: Network> Ledger<N> {
         Ok(transaction)
     }
 
-    /// Creates a transfer transaction.
-    pub fn create_transfer(&self, to: &Address<N>, amount: u64) -> Result<Transaction<N>> {
+    /// Creates a transfer transaction, applying the given dust policy to the change record.
+    pub fn create_transfer(&self, to: &Address<N>, amount: u64, dust_policy: DustPolicy) -> Result<Transaction<N>> {
+        self.create_transfer_with(to, amount, dust_policy, &RecordSelection::default())
+    }
+
+    /// Creates a transfer transaction, spending the record chosen by the given selector,
+    /// and applying the given dust policy to the change record.
+    #[cfg_attr(feature = "telemetry", tracing::instrument(skip_all))]
+    pub fn create_transfer_with(
+        &self,
+        to: &Address<N>,
+        amount: u64,
+        dust_policy: DustPolicy,
+        selector: &dyn RecordSelector,
+    ) -> Result<Transaction<N>> {
         // Fetch the unspent records.
         let records = self.find_unspent_records()?;
         ensure!(!records.len().is_zero(), "The Aleo account has no records to spend.");
 
-        // Initialize an RNG.
-        let rng = &mut ::rand::thread_rng();
+        // Select the records that cover the amount.
+        let threshold = dust_policy.threshold();
+        let mut candidates = records
+            .iter()
+            .filter(|(_, record)| ***record.gates() >= amount)
+            .collect::<Vec<_>>();
+        ensure!(
+            !candidates.is_empty(),
+            "The Aleo account has no record with at least {}.",
+            Credits::from_microcredits(amount)
+        );
+
+        // If the policy is to reject dust, only consider the records that do not leave dust as change.
+        if let DustPolicy::Reject { .. } = dust_policy {
+            candidates.retain(|(_, record)| {
+                let change = ***record.gates() - amount;
+                change.is_zero() || change >= threshold
+            });
+            ensure!(
+                !candidates.is_empty(),
+                "The transfer would leave a change record below the dust threshold of {}.",
+                Credits::from_microcredits(threshold)
+            );
+        }
+
+        // Spend the candidate the selector prefers.
+        let balances = candidates
+            .iter()
+            .map(|(_, record)| ***record.gates())
+            .collect::<Vec<_>>();
+        let (_, record) = selector
+            .select(&balances, amount, threshold)
+            .into_iter()
+            .find_map(|index| candidates.get(index).filter(|_| balances[index] >= amount))
+            .copied()
+            .ok_or_else(|| {
+                anyhow!(
+                    "The record selector did not select a record to transfer {}.",
+                    Credits::from_microcredits(amount)
+                )
+            })?;
 
         // Create a new transaction.
-        Transaction::execute(
-            &self.ledger.read().vm(),
-            &self.private_key,
+        self.create_execute(
             &ProgramID::from_str("credits.aleo")?,
             Identifier::from_str("transfer")?,
             &[
-                Value::Record(records.values().next().unwrap().clone()),
+                Value::Record(record.clone()),
                 Value::from_str(&format!("{to}"))?,
                 Value::from_str(&format!("{amount}u64"))?,
             ],
             None,
+        )
+    }
+
+    /// Creates an execute transaction for the given program function and inputs.
+    pub fn create_execute(
+        &self,
+        program_id: &ProgramID<N>,
+        function_name: Identifier<N>,
+        inputs: &[Value<N>],
+        additional_fee: Option<(Record<N, Plaintext<N>>, u64)>,
+    ) -> Result<Transaction<N>> {
+        // Initialize an RNG.
+        let rng = &mut ::rand::thread_rng();
+
+        // Create a new transaction.
+        Transaction::execute(
+            &self.ledger.read().vm(),
+            &self.private_key,
+            program_id,
+            function_name,
+            inputs,
+            additional_fee,
             rng,
         )
     }
 }
+
+/// Returns the number of whole block intervals in which no block was produced,
+/// given the production times of consecutive blocks, and the current time.
+fn missed_slots(times: &[Duration], now: Duration, interval: Duration) -> u64 {
+    let last = match times.last() {
+        Some(last) => *last,
+        None => return 0,
+    };
+    times
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .chain(std::iter::once((last, now)))
+        .map(|(start, end)| {
+            let slots = end.saturating_sub(start).as_millis() / interval.as_millis();
+            // The first interval of each gap is the one in which the next block is produced.
+            u64::try_from(slots).unwrap_or(u64::MAX).saturating_sub(1)
+        })
+        .fold(0, u64::saturating_add)
+}
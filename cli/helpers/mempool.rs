@@ -0,0 +1,374 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{bail, Result};
+use core::{hash::Hash, time::Duration};
+use indexmap::IndexMap;
+use serde::Serialize;
+
+/// A transaction waiting in the memory pool.
+#[derive(Clone, Debug)]
+pub struct PoolEntry<T> {
+    /// The transaction.
+    pub transaction: T,
+    /// The fee paid by the transaction.
+    pub fee: u64,
+    /// The sender of the transaction.
+    pub sender: String,
+    /// The size of the transaction, in bytes.
+    pub size: usize,
+    /// The time the transaction arrived.
+    pub arrived_at: Duration,
+    /// The order in which the transaction arrived.
+    pub sequence: u64,
+}
+
+/// The position of a transaction in the memory pool ordering.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct PoolPosition<K> {
+    /// The ID of the transaction.
+    pub id: K,
+    /// The fee paid by the transaction.
+    pub fee: u64,
+    /// The sender of the transaction.
+    pub sender: String,
+}
+
+/// The limits of the memory pool, where zero means no limit.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolLimits {
+    /// The maximum number of pending transactions per sender.
+    pub per_sender: usize,
+    /// The maximum number of pending transactions.
+    pub max_count: usize,
+    /// The maximum total size of the pending transactions, in bytes.
+    pub max_bytes: usize,
+    /// The time after which a pending transaction expires.
+    pub ttl: Duration,
+}
+
+/// The number of transactions evicted from the memory pool, by reason.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct PoolEvictions {
+    /// The number of transactions that expired.
+    pub expired: u64,
+    /// The number of transactions evicted to stay within the size caps.
+    pub capacity: u64,
+}
+
+/// A memory pool that orders its transactions by fee, highest first, and then by arrival,
+/// so that the selection into blocks is deterministic.
+#[derive(Clone, Debug)]
+pub struct MemoryPool<K: Hash + Eq, T> {
+    /// The pending transactions, in order of arrival.
+    entries: IndexMap<K, PoolEntry<T>>,
+    /// The limits of the pool.
+    limits: PoolLimits,
+    /// The number of evicted transactions.
+    evictions: PoolEvictions,
+    /// The sequence number of the next transaction.
+    next_sequence: u64,
+}
+
+impl<K: Hash + Eq, T> Default for MemoryPool<K, T> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+            limits: Default::default(),
+            evictions: Default::default(),
+            next_sequence: 0,
+        }
+    }
+}
+
+impl<K: Clone + Hash + Eq, T> MemoryPool<K, T> {
+    /// Returns the number of pending transactions.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no pending transactions.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns `true` if the given transaction is pending.
+    pub fn contains(&self, id: &K) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    /// Returns the given pending transaction.
+    pub fn get(&self, id: &K) -> Option<&T> {
+        self.entries.get(id).map(|entry| &entry.transaction)
+    }
+
+    /// Returns the total size of the pending transactions, in bytes.
+    pub fn size(&self) -> usize {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+
+    /// Returns the number of evicted transactions.
+    pub const fn evictions(&self) -> PoolEvictions {
+        self.evictions
+    }
+
+    /// Sets the limits of the pool, which apply from the next insertion.
+    pub fn set_limits(&mut self, limits: PoolLimits) {
+        self.limits = limits;
+    }
+
+    /// Adds the transaction to the pool, unless it is already pending or its sender is at capacity.
+    /// If the pool is full, the lowest priority transactions are evicted to make room, which fails
+    /// if the new transaction would itself be the one evicted.
+    pub fn insert(&mut self, id: K, transaction: T, fee: u64, sender: &str, size: usize, now: Duration) -> Result<()> {
+        // Evict the expired transactions.
+        self.evict_expired(now);
+
+        if self.entries.contains_key(&id) {
+            bail!("The transaction is already in the memory pool")
+        }
+        let per_sender = self.limits.per_sender;
+        if per_sender > 0 && self.entries.values().filter(|entry| entry.sender == sender).count() >= per_sender {
+            bail!("The sender '{sender}' already has {per_sender} pending transactions")
+        }
+        if self.limits.max_bytes > 0 && size > self.limits.max_bytes {
+            bail!(
+                "The transaction exceeds the memory pool size of {} bytes",
+                self.limits.max_bytes
+            )
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.entries.insert(id.clone(), PoolEntry {
+            transaction,
+            fee,
+            sender: sender.to_string(),
+            size,
+            arrived_at: now,
+            sequence,
+        });
+
+        // Evict the lowest priority transactions until the pool is within its caps.
+        let mut count = self.entries.len();
+        let mut bytes = self.size();
+        let mut ids = self.ordered_ids();
+        while (self.limits.max_count > 0 && count > self.limits.max_count)
+            || (self.limits.max_bytes > 0 && bytes > self.limits.max_bytes)
+        {
+            let lowest = match ids.pop() {
+                Some(lowest) => lowest,
+                None => break,
+            };
+            if lowest == id {
+                self.entries.shift_remove(&id);
+                bail!("The memory pool is full, and the transaction fee is too low")
+            }
+            if let Some(entry) = self.entries.shift_remove(&lowest) {
+                count -= 1;
+                bytes -= entry.size;
+                self.evictions.capacity += 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Evicts the transactions that have been pending for longer than the TTL.
+    pub fn evict_expired(&mut self, now: Duration) {
+        let ttl = self.limits.ttl;
+        if ttl.is_zero() {
+            return;
+        }
+        let len = self.entries.len();
+        self.entries
+            .retain(|_, entry| now.saturating_sub(entry.arrived_at) < ttl);
+        self.evictions.expired += (len - self.entries.len()) as u64;
+    }
+
+    /// Returns the IDs of the pending transactions, in the order they are selected into blocks.
+    fn ordered_ids(&self) -> Vec<K> {
+        let mut entries = self.entries.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, entry)| (core::cmp::Reverse(entry.fee), entry.sequence));
+        entries.into_iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Returns the pending transactions, in the order they are selected into blocks.
+    pub fn ordering(&self) -> Vec<PoolPosition<K>> {
+        self.ordered_ids()
+            .into_iter()
+            .map(|id| {
+                let entry = &self.entries[&id];
+                PoolPosition {
+                    fee: entry.fee,
+                    sender: entry.sender.clone(),
+                    id,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the pending transactions, in the order they are selected into blocks.
+    pub fn transactions(&self) -> Vec<&T> {
+        self.ordered_ids()
+            .iter()
+            .map(|id| &self.entries[id].transaction)
+            .collect()
+    }
+
+    /// Removes and returns up to `limit` transactions in order; zero means no limit.
+    pub fn select(&mut self, limit: usize) -> Vec<T> {
+        self.select_entries(limit)
+            .into_iter()
+            .map(|(_, entry)| entry.transaction)
+            .collect()
+    }
+
+    /// Removes and returns up to `limit` entries in order, with their IDs; zero means no limit.
+    pub fn select_entries(&mut self, limit: usize) -> Vec<(K, PoolEntry<T>)> {
+        let mut ids = self.ordered_ids();
+        if limit > 0 {
+            ids.truncate(limit);
+        }
+        ids.into_iter()
+            .filter_map(|id| self.entries.shift_remove(&id).map(|entry| (id, entry)))
+            .collect()
+    }
+
+    /// Puts the given selected entries back into the pool, keeping their arrival time and order,
+    /// and skipping the ones that are pending again.
+    pub fn restore(&mut self, entries: Vec<(K, PoolEntry<T>)>) {
+        for (id, entry) in entries {
+            self.entries.entry(id).or_insert(entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: Duration = Duration::from_secs(0);
+
+    #[test]
+    fn test_memory_pool_ordering() {
+        let mut pool = MemoryPool::default();
+        pool.insert(1, "a", 10, "alice", 1, NOW).unwrap();
+        pool.insert(2, "b", 30, "bob", 1, NOW).unwrap();
+        pool.insert(3, "c", 10, "bob", 1, NOW).unwrap();
+        pool.insert(4, "d", 20, "carol", 1, NOW).unwrap();
+        assert!(pool.insert(1, "a", 10, "alice", 1, NOW).is_err());
+
+        // Higher fees come first, and equal fees are ordered by arrival.
+        let ids = pool
+            .ordering()
+            .into_iter()
+            .map(|position| position.id)
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2, 4, 1, 3], ids);
+        assert_eq!(vec![&"b", &"d", &"a", &"c"], pool.transactions());
+        assert_eq!(Some(&"c"), pool.get(&3));
+        assert_eq!(None, pool.get(&5));
+
+        assert_eq!(vec!["b", "d"], pool.select(2));
+        assert_eq!(vec!["a", "c"], pool.select(0));
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn test_memory_pool_restore() {
+        let mut pool = MemoryPool::default();
+        pool.insert(1, "a", 10, "alice", 1, NOW).unwrap();
+        pool.insert(2, "b", 30, "bob", 1, NOW).unwrap();
+        pool.insert(3, "c", 10, "bob", 1, NOW).unwrap();
+
+        // The restored entries keep their place in the ordering.
+        let entries = pool.select_entries(2);
+        assert_eq!(vec![2, 1], entries.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+        pool.insert(4, "d", 10, "carol", 1, NOW).unwrap();
+        pool.restore(entries);
+        assert_eq!(vec![&"b", &"a", &"c", &"d"], pool.transactions());
+    }
+
+    #[test]
+    fn test_memory_pool_per_sender_cap() {
+        let mut pool = MemoryPool::default();
+        pool.set_limits(PoolLimits {
+            per_sender: 2,
+            ..Default::default()
+        });
+        pool.insert(1, (), 1, "alice", 1, NOW).unwrap();
+        pool.insert(2, (), 1, "alice", 1, NOW).unwrap();
+        assert!(pool.insert(3, (), 1, "alice", 1, NOW).is_err());
+        pool.insert(3, (), 1, "bob", 1, NOW).unwrap();
+
+        // Selecting frees up the sender's capacity.
+        pool.select(1);
+        pool.insert(4, (), 1, "alice", 1, NOW).unwrap();
+        assert_eq!(3, pool.len());
+    }
+
+    #[test]
+    fn test_memory_pool_eviction() {
+        let mut pool = MemoryPool::default();
+        pool.set_limits(PoolLimits {
+            max_count: 2,
+            max_bytes: 100,
+            ttl: Duration::from_secs(60),
+            ..Default::default()
+        });
+        pool.insert(1, (), 10, "alice", 40, NOW).unwrap();
+        pool.insert(2, (), 20, "alice", 40, NOW).unwrap();
+
+        // A higher fee evicts the lowest priority transaction, while a lower fee is rejected.
+        pool.insert(3, (), 30, "alice", 10, NOW).unwrap();
+        assert!(pool.insert(4, (), 5, "alice", 10, NOW).is_err());
+        assert_eq!(
+            vec![3, 2],
+            pool.ordering()
+                .into_iter()
+                .map(|position| position.id)
+                .collect::<Vec<_>>()
+        );
+
+        // The size cap also evicts.
+        pool.insert(5, (), 40, "alice", 60, NOW).unwrap();
+        assert_eq!(
+            vec![5, 3],
+            pool.ordering()
+                .into_iter()
+                .map(|position| position.id)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            PoolEvictions {
+                expired: 0,
+                capacity: 2
+            },
+            pool.evictions()
+        );
+
+        // Expired transactions are evicted.
+        pool.evict_expired(Duration::from_secs(60));
+        assert!(pool.is_empty());
+        assert_eq!(
+            PoolEvictions {
+                expired: 2,
+                capacity: 2
+            },
+            pool.evictions()
+        );
+    }
+}
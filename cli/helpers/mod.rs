@@ -14,11 +14,72 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
+pub mod abi;
+pub use abi::*;
+
+pub mod amm;
+pub use amm::*;
+
+pub mod clock;
+pub use clock::*;
+
+pub mod config;
+pub use config::*;
+
+pub mod credits;
+pub use credits::*;
+
+pub mod escrow;
+pub use escrow::*;
+
+pub mod governance;
+pub use governance::*;
+
+pub mod http;
+pub use http::*;
+
 pub mod ledger;
 pub use ledger::*;
 
+pub mod mempool;
+pub use mempool::*;
+
+pub mod network;
+pub use network::*;
+
+pub mod nft;
+pub use nft::*;
+
+pub mod payments;
+pub use payments::*;
+
+pub mod proof;
+pub use proof::*;
+
+pub mod ratelimit;
+pub use ratelimit::*;
+
+pub mod replay;
+pub use replay::*;
+
+pub mod selection;
+pub use selection::*;
+
+#[cfg(feature = "node")]
 pub mod server;
+#[cfg(feature = "node")]
 pub use server::*;
 
+pub mod service;
+pub use service::*;
+
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+#[cfg(feature = "telemetry")]
+pub use telemetry::*;
+
 pub mod updater;
 pub use updater::*;
+
+pub mod vesting;
+pub use vesting::*;
@@ -0,0 +1,106 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use snarkvm::prelude::{Network, Testnet3};
+
+use anyhow::{anyhow, Error, Result};
+use core::{fmt, str::FromStr};
+
+/// The networks the CLI can run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SupportedNetwork {
+    Testnet3,
+}
+
+impl SupportedNetwork {
+    /// The supported networks.
+    pub const ALL: &'static [Self] = &[Self::Testnet3];
+
+    /// Returns the network ID.
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::Testnet3 => Testnet3::ID,
+        }
+    }
+
+    /// Returns the network name, which prefixes the server routes.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Testnet3 => "testnet3",
+        }
+    }
+
+    /// Returns the supported network with the given ID.
+    pub fn from_id(id: u16) -> Result<Self> {
+        Self::ALL
+            .iter()
+            .find(|network| network.id() == id)
+            .copied()
+            .ok_or_else(|| anyhow!("Network {id} is not supported"))
+    }
+}
+
+impl Default for SupportedNetwork {
+    fn default() -> Self {
+        Self::Testnet3
+    }
+}
+
+impl FromStr for SupportedNetwork {
+    type Err = Error;
+
+    /// Parses a network from its name or ID.
+    fn from_str(network: &str) -> Result<Self> {
+        match network.parse::<u16>() {
+            Ok(id) => Self::from_id(id),
+            Err(_) => Self::ALL
+                .iter()
+                .find(|supported| supported.name().eq_ignore_ascii_case(network))
+                .copied()
+                .ok_or_else(|| {
+                    let names = Self::ALL.iter().map(|network| network.name()).collect::<Vec<_>>();
+                    anyhow!("Unknown network '{network}' (options: {})", names.join(", "))
+                }),
+        }
+    }
+}
+
+impl fmt::Display for SupportedNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_network() {
+        for network in SupportedNetwork::ALL {
+            assert_eq!(*network, SupportedNetwork::from_str(network.name()).unwrap());
+            assert_eq!(*network, SupportedNetwork::from_str(&network.id().to_string()).unwrap());
+            assert_eq!(*network, SupportedNetwork::from_id(network.id()).unwrap());
+        }
+        assert_eq!(
+            SupportedNetwork::Testnet3,
+            SupportedNetwork::from_str("Testnet3").unwrap()
+        );
+
+        assert!(SupportedNetwork::from_str("mainnet").is_err());
+        assert!(SupportedNetwork::from_id(u16::MAX).is_err());
+    }
+}
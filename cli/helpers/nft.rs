@@ -0,0 +1,177 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::Ledger;
+use snarkvm::prelude::{
+    Address,
+    Entry,
+    Field,
+    Identifier,
+    Network,
+    Plaintext,
+    ProgramID,
+    Record,
+    RecordsFilter,
+    Transaction,
+    Value,
+    ViewKey,
+};
+
+use anyhow::{anyhow, Result};
+use core::str::FromStr;
+use indexmap::IndexMap;
+
+/// An NFT record belonging to a conforming program.
+///
+/// A conforming program defines a record with a `token_id` entry, where every other
+/// data entry is treated as on-chain metadata, and exposes the following functions:
+///   - `mint(receiver: address, token_id, ...metadata)`
+///   - `transfer(token: record, receiver: address)`
+#[derive(Clone, Debug)]
+pub struct Nft<N: Network> {
+    /// The program ID of the NFT program.
+    program_id: ProgramID<N>,
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The token ID.
+    token_id: Plaintext<N>,
+    /// The metadata fields.
+    metadata: IndexMap<Identifier<N>, Plaintext<N>>,
+    /// The record.
+    record: Record<N, Plaintext<N>>,
+}
+
+impl<N: Network> Nft<N> {
+    /// The name of the mint function.
+    const MINT: &'static str = "mint";
+    /// The name of the token ID entry.
+    const TOKEN_ID: &'static str = "token_id";
+    /// The name of the transfer function.
+    const TRANSFER: &'static str = "transfer";
+
+    /// Parses an NFT from the given record, returning an error if it does not conform.
+    pub fn from_record(
+        program_id: ProgramID<N>,
+        commitment: Field<N>,
+        record: Record<N, Plaintext<N>>,
+    ) -> Result<Self> {
+        // Prepare the token ID identifier.
+        let token_id_name = Identifier::from_str(Self::TOKEN_ID)?;
+
+        // Parse the data entries.
+        let mut token_id = None;
+        let mut metadata = IndexMap::new();
+        for (name, entry) in record.data() {
+            let value = match entry {
+                Entry::Constant(plaintext) | Entry::Public(plaintext) | Entry::Private(plaintext) => plaintext.clone(),
+            };
+            match *name == token_id_name {
+                true => token_id = Some(value),
+                false => {
+                    metadata.insert(*name, value);
+                }
+            }
+        }
+        // Ensure the token ID is present.
+        let token_id =
+            token_id.ok_or_else(|| anyhow!("Record {commitment} is missing a '{}' entry", Self::TOKEN_ID))?;
+
+        Ok(Self {
+            program_id,
+            commitment,
+            token_id,
+            metadata,
+            record,
+        })
+    }
+
+    /// Returns the program ID of the NFT program.
+    pub const fn program_id(&self) -> &ProgramID<N> {
+        &self.program_id
+    }
+
+    /// Returns the record commitment.
+    pub const fn commitment(&self) -> &Field<N> {
+        &self.commitment
+    }
+
+    /// Returns the token ID.
+    pub const fn token_id(&self) -> &Plaintext<N> {
+        &self.token_id
+    }
+
+    /// Returns the metadata fields.
+    pub const fn metadata(&self) -> &IndexMap<Identifier<N>, Plaintext<N>> {
+        &self.metadata
+    }
+
+    /// Returns the record.
+    pub const fn record(&self) -> &Record<N, Plaintext<N>> {
+        &self.record
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Returns the NFTs of the given program that belong to the given view key.
+    pub fn find_nfts(
+        &self,
+        program_id: &ProgramID<N>,
+        view_key: &ViewKey<N>,
+        filter: RecordsFilter<N>,
+    ) -> Result<IndexMap<Field<N>, Nft<N>>> {
+        let commitments = self.program_commitments(program_id);
+        // Fetch the records of the view key, and parse the ones produced by the NFT program,
+        // skipping the program's other records.
+        Ok(self
+            .ledger
+            .read()
+            .find_records(view_key, filter)
+            .filter(|(commitment, _)| commitments.contains_key(commitment))
+            .filter_map(|(commitment, record)| {
+                let nft = Nft::from_record(*program_id, commitment, record).ok()?;
+                Some((commitment, nft))
+            })
+            .collect())
+    }
+
+    /// Creates a transaction minting a new NFT of the given program to the given receiver.
+    pub fn create_nft_mint(
+        &self,
+        program_id: &ProgramID<N>,
+        receiver: &Address<N>,
+        token_id: Value<N>,
+        metadata: &[Value<N>],
+    ) -> Result<Transaction<N>> {
+        // Prepare the inputs.
+        let mut inputs = vec![Value::from_str(&format!("{receiver}"))?, token_id];
+        inputs.extend_from_slice(metadata);
+        // Create the transaction.
+        self.create_execute(program_id, Identifier::from_str(Nft::<N>::MINT)?, &inputs, None)
+    }
+
+    /// Creates a transaction transferring the given NFT to the given receiver.
+    pub fn create_nft_transfer(&self, nft: &Nft<N>, receiver: &Address<N>) -> Result<Transaction<N>> {
+        self.create_execute(
+            nft.program_id(),
+            Identifier::from_str(Nft::<N>::TRANSFER)?,
+            &[
+                Value::Record(nft.record().clone()),
+                Value::from_str(&format!("{receiver}"))?,
+            ],
+            None,
+        )
+    }
+}
@@ -0,0 +1,287 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{DustPolicy, Ledger};
+use snarkvm::prelude::{Address, Field, Network, PrivateKey, Signature, Transaction, ViewKey};
+
+use anyhow::{ensure, Error, Result};
+use core::{fmt, str::FromStr};
+use rand::{CryptoRng, Rng};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// A payment request, signed by its recipient so the payer can trust its terms.
+///
+/// The request is fulfilled by a transfer of exactly the payment amount to the recipient, which the
+/// recipient can find on-chain with its view key, between the issue time and the expiry. As transfers
+/// carry no memo, the payment amount adds a tag derived from the terms to the requested amount, so
+/// the payments of concurrent requests of the same amount, such as two invoices, are told apart.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PaymentRequest<N: Network> {
+    /// The recipient of the payment.
+    pub recipient: Address<N>,
+    /// The requested amount, in microcredits.
+    pub amount: u64,
+    /// The time the request was issued, in seconds since the UNIX epoch.
+    pub issued_at: i64,
+    /// The time after which the request can no longer be fulfilled, in seconds since the UNIX epoch.
+    pub expiry: i64,
+    /// The reference of the payment, such as an invoice number.
+    pub reference: String,
+    /// The signature of the recipient over the terms.
+    pub signature: Signature<N>,
+}
+
+/// A payment found on-chain for a payment request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PaymentReceipt<N: Network> {
+    /// The commitment of the record the payment created.
+    pub commitment: Field<N>,
+    /// The height of the block containing the payment.
+    pub block_height: u32,
+}
+
+impl<N: Network> PaymentRequest<N> {
+    /// The maximum length of a reference, in bytes.
+    pub const MAX_REFERENCE_LENGTH: usize = 64;
+    /// The maximum tag added to the requested amount, in microcredits.
+    pub const MAX_TAG: u64 = 9_999;
+
+    /// Initializes a new payment request to the account of the given private key, signed by it.
+    pub fn new<R: Rng + CryptoRng>(
+        private_key: &PrivateKey<N>,
+        amount: u64,
+        issued_at: i64,
+        expiry: i64,
+        reference: String,
+        rng: &mut R,
+    ) -> Result<Self> {
+        ensure!(amount > 0, "The requested amount must be positive");
+        ensure!(amount <= u64::MAX - Self::MAX_TAG, "The requested amount is too large");
+        ensure!(expiry > issued_at, "The payment request must expire after it is issued");
+        ensure!(
+            reference.len() <= Self::MAX_REFERENCE_LENGTH,
+            "The reference exceeds {} bytes",
+            Self::MAX_REFERENCE_LENGTH
+        );
+
+        let recipient = Address::try_from(private_key)?;
+        let message = Self::message(&recipient, amount, issued_at, expiry, &reference);
+        let signature = Signature::sign_bytes(private_key, &message, rng)?;
+        Ok(Self {
+            recipient,
+            amount,
+            issued_at,
+            expiry,
+            reference,
+            signature,
+        })
+    }
+
+    /// Returns the message signed by the recipient, which covers all of the terms.
+    fn message(recipient: &Address<N>, amount: u64, issued_at: i64, expiry: i64, reference: &str) -> Vec<u8> {
+        format!("aleo-payment-request:{recipient}:{amount}:{issued_at}:{expiry}:{reference}").into_bytes()
+    }
+
+    /// Returns `true` if the request is signed by its recipient.
+    pub fn verify(&self) -> bool {
+        let message = Self::message(
+            &self.recipient,
+            self.amount,
+            self.issued_at,
+            self.expiry,
+            &self.reference,
+        );
+        self.signature.verify_bytes(&self.recipient, &message)
+    }
+
+    /// Returns the amount the payer transfers, in microcredits, which is the requested amount plus the tag of the request.
+    pub fn payment_amount(&self) -> u64 {
+        let message = Self::message(
+            &self.recipient,
+            self.amount,
+            self.issued_at,
+            self.expiry,
+            &self.reference,
+        );
+        self.amount.saturating_add(payment_tag(&message, Self::MAX_TAG))
+    }
+
+    /// Returns `true` if the request has expired at the given time, in seconds since the UNIX epoch.
+    pub const fn is_expired(&self, now: i64) -> bool {
+        now > self.expiry
+    }
+}
+
+/// Returns the tag of the given terms, between 1 and the given maximum, using the FNV-1a hash,
+/// which is stable across platforms and releases.
+fn payment_tag(message: &[u8], max_tag: u64) -> u64 {
+    let hash = message.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    hash % max_tag + 1
+}
+
+impl<N: Network> FromStr for PaymentRequest<N> {
+    type Err = Error;
+
+    /// Parses a payment request from its JSON encoding, and ensures it is signed by its recipient.
+    fn from_str(request: &str) -> Result<Self> {
+        let request: Self = serde_json::from_str(request)?;
+        ensure!(request.verify(), "The payment request is not signed by its recipient");
+        Ok(request)
+    }
+}
+
+impl<N: Network> fmt::Display for PaymentRequest<N> {
+    /// Prints the payment request as JSON.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).map_err(|_| fmt::Error)?)
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Creates a transfer that fulfills the given payment request, if it is valid and has not expired.
+    pub fn fulfill_payment(&self, request: &PaymentRequest<N>) -> Result<Transaction<N>> {
+        ensure!(request.verify(), "The payment request is not signed by its recipient");
+        ensure!(
+            !request.is_expired(self.clock().now().as_secs() as i64),
+            "The payment request expired at {}",
+            request.expiry
+        );
+        self.create_transfer(&request.recipient, request.payment_amount(), DustPolicy::default())
+    }
+
+    /// Returns the first payment of the payment amount of the request to the given view key, made in a block
+    /// between the issue time and the expiry of the request, if it exists.
+    ///
+    /// A payment is the receiver output of a `credits.aleo/transfer` transition whose change output does
+    /// not belong to the recipient, so the recipient's own transfers and change records are not payments.
+    pub fn find_payment(
+        &self,
+        request: &PaymentRequest<N>,
+        view_key: &ViewKey<N>,
+    ) -> Result<Option<PaymentReceipt<N>>> {
+        ensure!(request.verify(), "The payment request is not signed by its recipient");
+        ensure!(
+            Address::try_from(view_key)? == request.recipient,
+            "The view key does not belong to the recipient of the payment request"
+        );
+        let payment_amount = request.payment_amount();
+        let latest_height = self.ledger.read().latest_height();
+        for height in 0..=latest_height {
+            let block = self.ledger.read().get_block(height)?;
+            let timestamp = block.header().timestamp();
+            if timestamp < request.issued_at {
+                continue;
+            }
+            if timestamp > request.expiry {
+                break;
+            }
+            for transition in block.transitions().filter(|transition| {
+                transition.program_id().to_string() == "credits.aleo"
+                    && transition.function_name().to_string() == "transfer"
+            }) {
+                // The transfer outputs the receiver's record first, and the payer's change second.
+                let mut outputs = transition.output_records();
+                let ((commitment, payment), (_, change)) = match (outputs.next(), outputs.next()) {
+                    (Some(payment), Some(change)) => (payment, change),
+                    _ => continue,
+                };
+                // Skip the transfers that were not to the recipient, or that the recipient paid for.
+                if !payment.is_owner(&request.recipient, view_key) || change.is_owner(&request.recipient, view_key) {
+                    continue;
+                }
+                if ***payment.decrypt(view_key)?.gates() == payment_amount {
+                    return Ok(Some(PaymentReceipt {
+                        commitment: *commitment,
+                        block_height: height,
+                    }));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::sample_private_key;
+    use snarkvm::prelude::Testnet3;
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_payment_request_round_trip() {
+        let rng = &mut rand::thread_rng();
+        let private_key = sample_private_key::<CurrentNetwork, _>(rng).unwrap();
+        let request = PaymentRequest::new(&private_key, 1_000, 100, 200, "invoice-1".to_string(), rng).unwrap();
+        assert!(request.verify());
+        assert!(!request.is_expired(200));
+        assert!(request.is_expired(201));
+
+        // Serialize and parse the request.
+        let parsed = PaymentRequest::<CurrentNetwork>::from_str(&request.to_string()).unwrap();
+        assert_eq!(request, parsed);
+
+        // Tampered terms are rejected.
+        let mut tampered = request.clone();
+        tampered.amount = 1;
+        assert!(!tampered.verify());
+        assert!(PaymentRequest::<CurrentNetwork>::from_str(&tampered.to_string()).is_err());
+
+        // Invalid terms are rejected.
+        assert!(PaymentRequest::new(&private_key, 0, 100, 200, String::new(), rng).is_err());
+        assert!(PaymentRequest::new(&private_key, 1, 200, 100, String::new(), rng).is_err());
+        assert!(PaymentRequest::new(&private_key, u64::MAX, 100, 200, String::new(), rng).is_err());
+    }
+
+    #[test]
+    fn test_payment_amount() {
+        let rng = &mut rand::thread_rng();
+        let private_key = sample_private_key::<CurrentNetwork, _>(rng).unwrap();
+        let request = PaymentRequest::new(&private_key, 1_000, 100, 200, "invoice-1".to_string(), rng).unwrap();
+
+        // The payment amount adds a tag to the requested amount.
+        let payment_amount = request.payment_amount();
+        assert!(payment_amount > 1_000);
+        assert!(payment_amount <= 1_000 + PaymentRequest::<CurrentNetwork>::MAX_TAG);
+
+        // The tag only depends on the terms, so the payer and the recipient derive the same amount.
+        let parsed = PaymentRequest::<CurrentNetwork>::from_str(&request.to_string()).unwrap();
+        assert_eq!(payment_amount, parsed.payment_amount());
+    }
+
+    #[test]
+    fn test_payment_tag() {
+        // The tags are stable.
+        assert_eq!(payment_tag(b"", 9_999), 0xcbf2_9ce4_8422_2325 % 9_999 + 1);
+        assert_eq!(payment_tag(b"a", u64::MAX), 0xaf63_dc4c_8601_ec8c % u64::MAX + 1);
+        // Different references are tagged differently.
+        assert_ne!(
+            payment_tag(b"aleo-payment-request:invoice-1", 9_999),
+            payment_tag(b"aleo-payment-request:invoice-2", 9_999)
+        );
+        // The tags are within the range.
+        for reference in 0..1_000 {
+            let tag = payment_tag(format!("invoice-{reference}").as_bytes(), 10);
+            assert!((1..=10).contains(&tag));
+        }
+    }
+}
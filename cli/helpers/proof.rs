@@ -0,0 +1,207 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::Ledger;
+use snarkvm::prelude::{Field, FromBytes, Network, StatePath, ToBytes, Transition};
+
+use anyhow::{anyhow, ensure, Result};
+use serde::{Deserialize, Serialize};
+
+/// A link in a chain of blocks.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BlockLink<N: Network> {
+    /// The block height.
+    pub height: u32,
+    /// The block hash.
+    pub hash: N::BlockHash,
+    /// The hash of the previous block.
+    pub previous_hash: N::BlockHash,
+}
+
+/// A self-contained bundle of the evidence that a record exists on the ledger, for consumption by external chains.
+///
+/// The bundle contains the transition that created the record, the chain of blocks from the one
+/// containing the transition up to the latest block, and the state path of the record commitment
+/// against the state root at the latest block.
+///
+/// The state path proves the record commitment against the state root, through the transition, the
+/// transaction and the block containing them. The chain only links that block to the latest block by
+/// the hashes it claims, as the block headers are not part of the bundle.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct ProofBundle<N: Network> {
+    /// The version of the bundle format.
+    pub version: u16,
+    /// The record commitment.
+    pub commitment: Field<N>,
+    /// The transition that created the record.
+    pub transition: Transition<N>,
+    /// The blocks from the one containing the transition up to the latest block.
+    pub chain: Vec<BlockLink<N>>,
+    /// The state root the state path is proven against.
+    pub state_root: Field<N>,
+    /// The state path of the record commitment.
+    pub state_path: StatePath<N>,
+}
+
+impl<N: Network> ProofBundle<N> {
+    /// The current version of the bundle format.
+    pub const VERSION: u16 = 1;
+
+    /// Verifies that the record exists on the ledger with the given trusted state root.
+    pub fn verify(&self, trusted_state_root: &Field<N>) -> Result<()> {
+        ensure!(
+            self.version == Self::VERSION,
+            "Unsupported proof bundle version {}",
+            self.version
+        );
+        ensure!(
+            &self.state_root == trusted_state_root,
+            "The proof bundle is for a different state root"
+        );
+        // Recompute the hashes of the state path, which are checked when it is parsed.
+        let state_path = StatePath::<N>::from_bytes_le(&self.state_path.to_bytes_le()?)
+            .map_err(|error| anyhow!("The state path is invalid: {error}"))?;
+        // Ensure the state path proves the record commitment against the state root.
+        ensure!(
+            state_path.global_state_root() == self.state_root,
+            "The state path is for a different state root"
+        );
+        ensure!(
+            state_path.transition_leaf().id() == self.commitment,
+            "The state path is for a different record"
+        );
+        // Ensure the transition created the record, and is the one in the state path.
+        ensure!(
+            self.transition
+                .commitments()
+                .any(|commitment| *commitment == self.commitment),
+            "The transition did not create the record"
+        );
+        ensure!(
+            state_path.transaction_leaf().id() == **self.transition.id(),
+            "The state path is for a different transition"
+        );
+        // Ensure the chain starts at the block in the state path, and is contiguous.
+        ensure!(!self.chain.is_empty(), "The proof bundle contains no blocks");
+        ensure!(
+            state_path.block_hash() == self.chain[0].hash,
+            "The proof bundle chain does not start at the block containing the record"
+        );
+        for pair in self.chain.windows(2) {
+            ensure!(
+                pair[0].height.checked_add(1) == Some(pair[1].height) && pair[1].previous_hash == pair[0].hash,
+                "The proof bundle chain is broken at block {}",
+                pair[1].height
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<N: Network> Ledger<N> {
+    /// Exports a proof bundle for the record with the given commitment.
+    pub fn export_proof_bundle(&self, commitment: &Field<N>) -> Result<ProofBundle<N>> {
+        // Find the transition that created the record.
+        let origin = self
+            .find_record(commitment, None)?
+            .ok_or_else(|| anyhow!("Record '{commitment}' does not exist"))?;
+        let start_height = origin.block_height;
+        let ledger = self.ledger.read();
+        let transition = ledger
+            .get_block(start_height)?
+            .transitions()
+            .find(|transition| *transition.id() == origin.transition_id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Record '{commitment}' does not exist"))?;
+        // Collect the chain from the block containing the transition.
+        let chain = (start_height..=ledger.latest_height())
+            .map(|height| {
+                let block = ledger.get_block(height)?;
+                Ok(BlockLink {
+                    height,
+                    hash: block.hash(),
+                    previous_hash: block.previous_hash(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ProofBundle {
+            version: ProofBundle::<N>::VERSION,
+            commitment: *commitment,
+            transition,
+            chain,
+            state_root: *ledger.latest_state_root(),
+            state_path: ledger.to_state_path(commitment)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{sample_accounts, sample_chain, sample_private_key};
+    use snarkvm::prelude::{Testnet3, Zero};
+
+    type CurrentNetwork = Testnet3;
+
+    #[test]
+    fn test_proof_bundle_round_trip() {
+        let rng = &mut rand::thread_rng();
+        let private_key = sample_private_key::<CurrentNetwork, _>(rng).unwrap();
+        let recipients = sample_accounts::<CurrentNetwork, _>(1, rng).unwrap();
+        let recipients = recipients.into_iter().map(|(_, address)| address).collect::<Vec<_>>();
+        let (ledger, blocks) = sample_chain(&private_key, &recipients, 2, rng).unwrap();
+
+        // Export a bundle for a record created in the first block after genesis.
+        let commitment = *blocks[1]
+            .transitions()
+            .flat_map(|transition| transition.commitments())
+            .next()
+            .unwrap();
+        let bundle = ledger.export_proof_bundle(&commitment).unwrap();
+        assert_eq!(2, bundle.chain.len());
+
+        // Serialize and deserialize the bundle, and verify it.
+        let bundle: ProofBundle<CurrentNetwork> =
+            serde_json::from_str(&serde_json::to_string(&bundle).unwrap()).unwrap();
+        let state_root = *ledger.ledger.read().latest_state_root();
+        bundle.verify(&state_root).unwrap();
+
+        // A different state root is rejected.
+        assert!(bundle.verify(&Field::zero()).is_err());
+
+        // A bundle for a different record is rejected.
+        let mut forged = bundle.clone();
+        forged.commitment = *blocks[1]
+            .transitions()
+            .flat_map(|transition| transition.commitments())
+            .nth(1)
+            .unwrap();
+        assert!(forged.verify(&state_root).is_err());
+
+        // A bundle claiming a different transition is rejected.
+        let mut forged = bundle.clone();
+        forged.transition = blocks[0].transitions().next().unwrap().clone();
+        assert!(forged.verify(&state_root).is_err());
+
+        // A bundle claiming a different chain is rejected.
+        let mut forged = bundle;
+        forged.chain.remove(0);
+        assert!(forged.verify(&state_root).is_err());
+    }
+}
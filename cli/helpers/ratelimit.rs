@@ -0,0 +1,101 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use core::{hash::Hash, time::Duration};
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+
+/// A token bucket per key, used to rate limit requests.
+#[derive(Debug)]
+pub struct RateLimiter<K: Hash + Eq> {
+    /// The number of tokens added to a bucket per second.
+    rate: f64,
+    /// The maximum number of tokens in a bucket.
+    burst: f64,
+    /// The map of buckets, to their number of tokens and the time they were last refilled.
+    buckets: Mutex<IndexMap<K, (f64, Duration)>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    /// The number of buckets above which the full buckets are forgotten.
+    const MAX_BUCKETS: usize = 1024;
+
+    /// Initializes a new rate limiter with the given rate, in requests per second, and burst.
+    /// A burst of zero allows one second worth of requests.
+    pub fn new(rate: f64, burst: u32) -> Self {
+        Self {
+            rate,
+            burst: match burst {
+                0 => rate.ceil().max(1.0),
+                burst => f64::from(burst),
+            },
+            buckets: Default::default(),
+        }
+    }
+
+    /// Takes a token from the bucket of the key, returning `false` if the bucket is empty.
+    pub fn check(&self, key: K, now: Duration) -> bool {
+        let mut buckets = self.buckets.lock();
+        // Forget the full buckets, which are equivalent to new ones.
+        if buckets.len() >= Self::MAX_BUCKETS {
+            let (rate, burst) = (self.rate, self.burst);
+            buckets.retain(|_, (tokens, refilled_at)| {
+                *tokens + now.saturating_sub(*refilled_at).as_secs_f64() * rate < burst
+            });
+        }
+        // Refill the bucket, and take a token from it.
+        let (tokens, refilled_at) = buckets.entry(key).or_insert((self.burst, now));
+        *tokens = (*tokens + now.saturating_sub(*refilled_at).as_secs_f64() * self.rate).min(self.burst);
+        *refilled_at = now;
+        match *tokens >= 1.0 {
+            true => {
+                *tokens -= 1.0;
+                true
+            }
+            false => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2.0, 3);
+        let now = Duration::from_secs(0);
+
+        // The burst is allowed, and then the bucket is empty.
+        assert!(limiter.check("a", now));
+        assert!(limiter.check("a", now));
+        assert!(limiter.check("a", now));
+        assert!(!limiter.check("a", now));
+        // Other keys have their own bucket.
+        assert!(limiter.check("b", now));
+
+        // The bucket refills at the rate.
+        assert!(limiter.check("a", Duration::from_millis(500)));
+        assert!(!limiter.check("a", Duration::from_millis(500)));
+
+        // The bucket does not refill beyond the burst.
+        let later = Duration::from_secs(60);
+        assert!(limiter.check("a", later));
+        assert!(limiter.check("a", later));
+        assert!(limiter.check("a", later));
+        assert!(!limiter.check("a", later));
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use core::{hash::Hash, time::Duration};
+use indexmap::IndexMap;
+use parking_lot::Mutex;
+
+/// A window of recently seen keys, used to reject replayed requests.
+#[derive(Debug)]
+pub struct ReplayWindow<K: Hash + Eq> {
+    /// The duration for which a key is remembered.
+    ttl: Duration,
+    /// The map of seen keys, to the time they were seen and their original status.
+    seen: Mutex<IndexMap<K, (Duration, String)>>,
+}
+
+impl<K: Hash + Eq> ReplayWindow<K> {
+    /// Initializes a new replay window with the given TTL.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            seen: Default::default(),
+        }
+    }
+
+    /// Records the key with the given status, unless it was already seen within the window,
+    /// in which case the original status is returned instead.
+    pub fn check_and_insert(&self, key: K, now: Duration, status: &str) -> Option<String> {
+        let mut seen = self.seen.lock();
+        // Forget the expired keys.
+        let ttl = self.ttl;
+        seen.retain(|_, (seen_at, _)| now.saturating_sub(*seen_at) < ttl);
+        // Check for a replay.
+        if let Some((_, original_status)) = seen.get(&key) {
+            return Some(original_status.clone());
+        }
+        seen.insert(key, (now, status.to_string()));
+        None
+    }
+
+    /// Forgets the given key, e.g. if the original request failed and may be retried.
+    pub fn remove(&self, key: &K) {
+        self.seen.lock().shift_remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay_window() {
+        let window = ReplayWindow::new(Duration::from_secs(60));
+
+        assert_eq!(None, window.check_and_insert("a", Duration::from_secs(0), "OK"));
+        assert_eq!(
+            Some("OK".to_string()),
+            window.check_and_insert("a", Duration::from_secs(59), "OK")
+        );
+        assert_eq!(None, window.check_and_insert("b", Duration::from_secs(59), "OK"));
+
+        // Expired keys may be inserted again.
+        assert_eq!(None, window.check_and_insert("a", Duration::from_secs(60), "OK"));
+
+        // Removed keys may be inserted again.
+        window.remove(&"b");
+        assert_eq!(None, window.check_and_insert("b", Duration::from_secs(61), "OK"));
+    }
+}
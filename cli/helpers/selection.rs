@@ -0,0 +1,97 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+/// A strategy to choose which of the unspent records to spend.
+///
+/// Custom strategies can be passed to `Ledger::create_transfer_with` by implementing this trait.
+pub trait RecordSelector {
+    /// Returns the indices of the given record balances to spend the amount from, most preferred first.
+    ///
+    /// The balances are of the records that cover the amount, in the order they were created.
+    /// A record whose change would be below the dust threshold (in gates) leaves dust.
+    fn select(&self, balances: &[u64], amount: u64, dust_threshold: u64) -> Vec<usize>;
+}
+
+/// The built-in record selection strategies.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RecordSelection {
+    /// Spends the largest record first.
+    LargestFirst,
+    /// Spends the smallest record first.
+    SmallestFirst,
+    /// Spends only a record whose balance is exactly the amount.
+    ExactMatch,
+    /// Spends the record leaving the least change that is not dust, or the least dust otherwise.
+    MinimizeChange,
+}
+
+impl Default for RecordSelection {
+    fn default() -> Self {
+        Self::MinimizeChange
+    }
+}
+
+impl RecordSelector for RecordSelection {
+    fn select(&self, balances: &[u64], amount: u64, dust_threshold: u64) -> Vec<usize> {
+        let mut indices = (0..balances.len())
+            .filter(|i| balances[*i] >= amount)
+            .collect::<Vec<_>>();
+        // Sorting is stable, so equal balances are spent in the order they were created.
+        match self {
+            Self::LargestFirst => indices.sort_by_key(|i| core::cmp::Reverse(balances[*i])),
+            Self::SmallestFirst => indices.sort_by_key(|i| balances[*i]),
+            Self::ExactMatch => indices.retain(|i| balances[*i] == amount),
+            Self::MinimizeChange => indices.sort_by_key(|i| {
+                let change = balances[*i] - amount;
+                (change != 0 && change < dust_threshold, change)
+            }),
+        }
+        indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_selection() {
+        let balances = [50, 10, 300, 105, 10, 5];
+
+        assert_eq!(
+            vec![2, 3, 0, 1, 4],
+            RecordSelection::LargestFirst.select(&balances, 10, 0)
+        );
+        assert_eq!(
+            vec![1, 4, 0, 3, 2],
+            RecordSelection::SmallestFirst.select(&balances, 10, 0)
+        );
+        assert_eq!(vec![1, 4], RecordSelection::ExactMatch.select(&balances, 10, 0));
+        assert!(RecordSelection::ExactMatch.select(&balances, 11, 0).is_empty());
+
+        // Without a dust threshold, the change is minimized.
+        assert_eq!(vec![3, 2], RecordSelection::MinimizeChange.select(&balances, 100, 0));
+        // Records that would leave dust are spent last.
+        assert_eq!(vec![2, 3], RecordSelection::MinimizeChange.select(&balances, 100, 10));
+        assert_eq!(
+            vec![0, 2, 3],
+            RecordSelection::MinimizeChange.select(&balances, 50, 100)
+        );
+
+        // No record covers the amount.
+        assert!(RecordSelection::LargestFirst.select(&balances, 301, 0).is_empty());
+    }
+}
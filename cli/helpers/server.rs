@@ -1,25 +1,153 @@
-use crate::helpers::Ledger;
-use snarkvm::prelude::{Field, GraphKey, Network, RecordsFilter, Transaction, ViewKey};
+use crate::helpers::{
+    notify,
+    Ledger,
+    PaymentRequest,
+    ProgramAbi,
+    RateLimiter,
+    RecordsPage,
+    ReplayWindow,
+    SupportedNetwork,
+    TransactionStatus,
+};
+use snarkvm::prelude::{
+    Address,
+    Field,
+    FromBytes,
+    GraphKey,
+    Identifier,
+    Network,
+    ProgramID,
+    RecordsFilter,
+    ToBytes,
+    Transaction,
+    Transition,
+    Value,
+    ViewKey,
+};
 
-use anyhow::Result;
-use core::marker::PhantomData;
+use anyhow::{anyhow, ensure, Result};
+use colored::Colorize;
+use core::{fmt, future::Future, marker::PhantomData, pin::Pin, str::FromStr, time::Duration};
+use futures_util::{stream, SinkExt, StreamExt};
 use indexmap::IndexMap;
-use std::sync::Arc;
-use tokio::{sync::mpsc, task::JoinHandle};
-use warp::{http::StatusCode, reject, reply, Filter, Rejection, Reply};
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use once_cell::sync::OnceCell;
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::{Infallible, TryFrom},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::{
+    sync::{broadcast::error::RecvError, mpsc, watch, Semaphore},
+    task::JoinHandle,
+};
+use warp::{
+    http::StatusCode,
+    path::FullPath,
+    reject,
+    reply,
+    sse,
+    ws::{Message, WebSocket, Ws},
+    Filter,
+    Rejection,
+    Reply,
+};
 
 /// An enum of error handlers for the server.
 #[derive(Debug)]
 enum ServerError {
     Request(String),
+    NotFound(String),
+    InvalidTransaction(String),
+    Unauthorized,
+    Forbidden,
+    InjectedFault,
+    Replay(String),
+    Conflict(String),
+    Unavailable(String),
+    Internal(String),
+    RateLimited,
 }
 
 impl reject::Reject for ServerError {}
 
+impl ServerError {
+    /// Returns the status code and the message of the error.
+    fn status_and_message(&self) -> (StatusCode, String) {
+        match self {
+            Self::Request(message) => (StatusCode::BAD_REQUEST, message.clone()),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message.clone()),
+            Self::InvalidTransaction(message) => (StatusCode::UNPROCESSABLE_ENTITY, message.clone()),
+            Self::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
+            Self::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string()),
+            Self::InjectedFault => (StatusCode::INTERNAL_SERVER_ERROR, "Injected fault".to_string()),
+            Self::Replay(status) => (
+                StatusCode::CONFLICT,
+                format!("Replayed transaction (original status: {status})"),
+            ),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message.clone()),
+            Self::Unavailable(message) => (StatusCode::SERVICE_UNAVAILABLE, message.clone()),
+            Self::Internal(message) => (StatusCode::INTERNAL_SERVER_ERROR, message.clone()),
+            Self::RateLimited => (StatusCode::TOO_MANY_REQUESTS, "Too many requests".to_string()),
+        }
+    }
+}
+
+/// The body of an error response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// The HTTP status code.
+    pub code: u16,
+    /// The description of the error.
+    pub message: String,
+}
+
+/// Recovers the rejections into JSON error responses with their status codes.
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let (status, message) = if let Some(error) = rejection.find::<ServerError>() {
+        error.status_and_message()
+    } else if rejection.is_not_found() {
+        (StatusCode::NOT_FOUND, "Not found".to_string())
+    } else if let Some(error) = rejection.find::<warp::body::BodyDeserializeError>() {
+        (StatusCode::BAD_REQUEST, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::InvalidQuery>() {
+        (StatusCode::BAD_REQUEST, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::InvalidHeader>() {
+        (StatusCode::BAD_REQUEST, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::MissingHeader>() {
+        (StatusCode::BAD_REQUEST, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::LengthRequired>() {
+        (StatusCode::LENGTH_REQUIRED, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::PayloadTooLarge>() {
+        (StatusCode::PAYLOAD_TOO_LARGE, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::UnsupportedMediaType>() {
+        (StatusCode::UNSUPPORTED_MEDIA_TYPE, error.to_string())
+    } else if let Some(error) = rejection.find::<reject::MethodNotAllowed>() {
+        (StatusCode::METHOD_NOT_ALLOWED, error.to_string())
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Unhandled rejection: {rejection:?}"),
+        )
+    };
+    let body = ErrorResponse {
+        code: status.as_u16(),
+        message,
+    };
+    Ok(reply::with_status(reply::json(&body), status))
+}
+
 /// A trait to unwrap a `Result` or `Reject`.
 pub trait OrReject<T> {
     /// Returns the result if it is successful, otherwise returns a rejection.
     fn or_reject(self) -> Result<T, Rejection>;
+
+    /// Returns the result if it is successful, otherwise returns a not found rejection.
+    fn or_not_found(self) -> Result<T, Rejection>;
 }
 
 impl<T> OrReject<T> for anyhow::Result<T> {
@@ -27,6 +155,11 @@ impl<T> OrReject<T> for anyhow::Result<T> {
     fn or_reject(self) -> Result<T, Rejection> {
         Ok(self.map_err(|e| reject::custom(ServerError::Request(e.to_string())))?)
     }
+
+    /// Returns the result if it is successful, otherwise returns a not found rejection.
+    fn or_not_found(self) -> Result<T, Rejection> {
+        Ok(self.map_err(|e| reject::custom(ServerError::NotFound(e.to_string())))?)
+    }
 }
 
 /// A middleware to include the given item in the handler.
@@ -34,6 +167,436 @@ fn with<T: Clone + Send>(item: T) -> impl Filter<Extract = (T,), Error = std::co
     warp::any().map(move || item.clone())
 }
 
+/// The roles that can be granted to an API key, in increasing order of privilege.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Role {
+    /// Allows reading the ledger.
+    ReadOnly,
+    /// Allows reading the ledger and broadcasting transactions.
+    Broadcast,
+    /// Allows all operations, including the admin endpoints.
+    Admin,
+}
+
+impl FromStr for Role {
+    type Err = anyhow::Error;
+
+    fn from_str(role: &str) -> Result<Self> {
+        match role {
+            "read" | "read-only" => Ok(Self::ReadOnly),
+            "broadcast" => Ok(Self::Broadcast),
+            "admin" => Ok(Self::Admin),
+            _ => anyhow::bail!("Invalid role '{role}', expected one of 'read-only', 'broadcast', or 'admin'"),
+        }
+    }
+}
+
+/// The handle to the process-wide metrics recorder.
+static METRICS: OnceCell<PrometheusHandle> = OnceCell::new();
+
+/// Installs the metrics recorder, if it is not installed yet, and returns its handle.
+fn metrics_handle() -> Result<PrometheusHandle> {
+    METRICS
+        .get_or_try_init(|| PrometheusBuilder::new().install_recorder())
+        .map(Clone::clone)
+        .map_err(|error| anyhow!("Failed to install the metrics recorder: {error}"))
+}
+
+/// Records the count and latency of the served requests.
+fn record_request(info: warp::log::Info) {
+    let method = info.method().to_string();
+    let status = info.status().as_u16().to_string();
+    metrics::increment_counter!("aleo_http_requests_total", "method" => method.clone(), "status" => status);
+    metrics::histogram!("aleo_http_request_duration_seconds", info.elapsed().as_secs_f64(), "method" => method);
+}
+
+/// A middleware to reject requests whose API key does not grant the given role.
+fn authorize(config: Arc<RwLock<ServerConfig>>, role: Role) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::header::optional::<String>("X-Aleo-API-Key")
+        .and(with(config))
+        .and_then(
+            move |api_key: Option<String>, config: Arc<RwLock<ServerConfig>>| async move {
+                config
+                    .read()
+                    .authorize(api_key.as_deref(), role)
+                    .map_err(reject::custom)
+            },
+        )
+        .untuple_one()
+}
+
+/// The faults to inject into the requests matching a route prefix.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Fault {
+    /// The latency to add to each request, in milliseconds.
+    pub latency_ms: u64,
+    /// The probability of responding with a 500 error.
+    pub error_rate: f64,
+    /// The probability of never responding.
+    pub drop_rate: f64,
+}
+
+/// Shorthand for the faults to inject, keyed by route prefix.
+type Faults = Arc<RwLock<IndexMap<String, Fault>>>;
+
+/// A middleware to inject the configured faults into the requests, where each request
+/// uses the fault of its longest matching route prefix. The admin endpoints are exempt.
+fn inject_faults(faults: Faults) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::full()
+        .and(with(faults))
+        .and_then(|path: FullPath, faults: Faults| async move {
+            // Find the fault for the request.
+            let path = path.as_str();
+            let fault = match path.contains("/admin/") {
+                true => None,
+                false => faults
+                    .read()
+                    .iter()
+                    .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+                    .max_by_key(|(prefix, _)| prefix.len())
+                    .map(|(_, fault)| *fault),
+            };
+            let fault = match fault {
+                Some(fault) => fault,
+                None => return Ok(()),
+            };
+
+            // Sample the outcome of the request.
+            let (is_error, is_dropped) = {
+                let rng = &mut rand::thread_rng();
+                (rng.gen_bool(fault.error_rate), rng.gen_bool(fault.drop_rate))
+            };
+            // Inject the latency.
+            tokio::time::sleep(Duration::from_millis(fault.latency_ms)).await;
+            // Inject the dropped response.
+            if is_dropped {
+                std::future::pending::<()>().await;
+            }
+            // Inject the error.
+            match is_error {
+                true => Err(reject::custom(ServerError::InjectedFault)),
+                false => Ok(()),
+            }
+        })
+        .untuple_one()
+}
+
+/// A middleware to rate limit the requests by the IP address of the client, if a rate limiter is given.
+fn rate_limit<N: Network>(
+    limiter: Option<Arc<RateLimiter<IpAddr>>>,
+    ledger: Arc<Ledger<N>>,
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::addr::remote()
+        .and(with(limiter))
+        .and(with(ledger))
+        .and_then(
+            |remote: Option<SocketAddr>, limiter: Option<Arc<RateLimiter<IpAddr>>>, ledger: Arc<Ledger<N>>| async move {
+                match (limiter, remote) {
+                    (Some(limiter), Some(remote)) if !limiter.check(remote.ip(), ledger.clock().now()) => {
+                        Err(reject::custom(ServerError::RateLimited))
+                    }
+                    _ => Ok(()),
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Shorthand for the view keys registered for record notifications, keyed by subscription ID.
+type Subscriptions<N> = Arc<RwLock<IndexMap<String, ViewKey<N>>>>;
+
+/// The response to a subscription registration.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Subscription {
+    /// The ID of the subscription.
+    pub id: String,
+}
+
+/// The serialization mode for integers in responses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IntegerMode {
+    /// Serializes integers as JSON numbers.
+    Number,
+    /// Serializes integers as JSON strings, so they do not lose precision in JavaScript consumers.
+    String,
+}
+
+/// The casing of the field names in responses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FieldCasing {
+    /// Keeps the field names in snake case, as the snarkOS node returns them.
+    Snake,
+    /// Renames the fields of the node's own response structs to their snarkOS-style camel case aliases,
+    /// for JavaScript consumers.
+    Camel,
+}
+
+/// The serialization format of responses.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ResponseFormat {
+    /// The serialization mode for integers.
+    pub integers: IntegerMode,
+    /// The casing of the field names.
+    pub casing: FieldCasing,
+}
+
+impl ResponseFormat {
+    /// Returns `true` if values are serialized as they are.
+    fn is_default(&self) -> bool {
+        self.integers == IntegerMode::Number && self.casing == FieldCasing::Snake
+    }
+
+    /// Applies the format to the given JSON value, where the field aliases are only applied
+    /// if the value is one of the node's own response structs.
+    fn apply(&self, value: &mut serde_json::Value, aliased: bool) {
+        if self.integers == IntegerMode::String {
+            stringify_integers(value);
+        }
+        if self.casing == FieldCasing::Camel && aliased {
+            alias_fields(value);
+        }
+    }
+}
+
+/// A summary of the latest block, for dashboards that would otherwise poll several endpoints.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct LatestSummary<N: Network> {
+    /// The latest block height.
+    pub height: u32,
+    /// The latest block hash.
+    pub hash: N::BlockHash,
+    /// The latest state root.
+    pub state_root: Field<N>,
+    /// The timestamp of the latest block.
+    pub timestamp: i64,
+    /// The number of transactions in the latest block.
+    pub transactions: usize,
+    /// The proof target of the latest block.
+    pub proof_target: u64,
+}
+
+/// The diagnostics of the node, to investigate its resource usage in production.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    /// The number of broadcast transactions waiting in the ledger queue.
+    pub ledger_queue: usize,
+    /// The number of transactions being verified.
+    pub verification_tasks: usize,
+    /// The number of block subscribers, including the record subscriptions being streamed.
+    pub block_subscribers: usize,
+    /// The number of registered record subscriptions.
+    pub record_subscriptions: usize,
+    /// The number of blocks in the ledger.
+    pub blocks: u32,
+    /// The number of transactions in the memory pool.
+    pub memory_pool_transactions: usize,
+    /// The total size of the transactions in the memory pool, in bytes.
+    pub memory_pool_bytes: usize,
+}
+
+/// The result of verifying a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Verification {
+    /// Whether the transaction and its proofs are valid.
+    pub valid: bool,
+}
+
+/// A request to execute a program function without broadcasting the execution.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DryRunRequest {
+    /// The program ID, such as `credits.aleo`.
+    pub program_id: String,
+    /// The function name.
+    pub function: String,
+    /// The inputs, such as `1u64` or a record plaintext.
+    pub inputs: Vec<String>,
+}
+
+/// The result of executing a program function without broadcasting the execution.
+#[derive(Clone, Debug, Serialize)]
+#[serde(bound = "")]
+pub struct DryRun<N: Network> {
+    /// The transitions of the execution, which contain its outputs.
+    pub transitions: Vec<Transition<N>>,
+    /// The fee of the execution, in gates.
+    pub fee: u64,
+    /// The size of the execution transaction, in bytes.
+    pub size_in_bytes: usize,
+}
+
+/// Converts all of the integers in the given JSON value into strings.
+fn stringify_integers(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Number(number) if !number.is_f64() => {
+            let integer = number.to_string();
+            *value = integer.into();
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(stringify_integers),
+        serde_json::Value::Object(values) => values.values_mut().for_each(stringify_integers),
+        _ => (),
+    }
+}
+
+/// The snarkOS-style aliases of the fields of the node's own response structs, as `(field, alias)`.
+const FIELD_ALIASES: &[(&str, &str)] = &[
+    ("block_height", "blockHeight"),
+    ("block_reward", "blockReward"),
+    ("last_block_age_secs", "lastBlockAgeSecs"),
+    ("last_block_time", "lastBlockTime"),
+    ("latest_height", "latestHeight"),
+    ("missed_slots", "missedSlots"),
+    ("program_id", "programId"),
+    ("proof_target", "proofTarget"),
+    ("recent_blocks", "recentBlocks"),
+    ("schedule_position", "schedulePosition"),
+    ("serial_number", "serialNumber"),
+    ("size_in_bytes", "sizeInBytes"),
+    ("state_root", "stateRoot"),
+    ("transaction_id", "transactionId"),
+    ("transition_id", "transitionId"),
+    ("uptime_secs", "uptimeSecs"),
+];
+
+/// Renames the fields of the given response struct, or of each response struct in the given list,
+/// to their aliases, leaving the nested values as they are.
+fn alias_fields(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Array(values) => values.iter_mut().for_each(alias_fields),
+        serde_json::Value::Object(fields) => {
+            *fields = core::mem::take(fields)
+                .into_iter()
+                .map(
+                    |(name, value)| match FIELD_ALIASES.iter().find(|(field, _)| *field == name) {
+                        Some((_, alias)) => (alias.to_string(), value),
+                        None => (name, value),
+                    },
+                )
+                .collect();
+        }
+        _ => (),
+    }
+}
+
+/// The query parameters of a block range, where `end` is exclusive.
+#[derive(Deserialize)]
+struct BlocksRangeQuery {
+    start: u32,
+    end: u32,
+}
+
+/// The query parameters of a block subscription.
+#[derive(Deserialize)]
+struct BlocksQuery {
+    header: Option<bool>,
+}
+
+/// The query parameters of a record lookup.
+#[derive(Deserialize)]
+struct RecordQuery {
+    serial_number: Option<String>,
+}
+
+/// The query parameters selecting the response format.
+#[derive(Deserialize)]
+struct FormatQuery {
+    integers: Option<String>,
+    casing: Option<String>,
+}
+
+/// A middleware to extract the response format from the `integers` and `casing` query parameters,
+/// or the `X-Aleo-Integers` and `X-Aleo-Casing` headers.
+fn response_format() -> impl Filter<Extract = (ResponseFormat,), Error = Rejection> + Clone {
+    warp::query::<FormatQuery>()
+        .map(|query: FormatQuery| (query.integers, query.casing))
+        .or(warp::any().map(|| (None, None)))
+        .unify()
+        .and(warp::header::optional::<String>("X-Aleo-Integers"))
+        .and(warp::header::optional::<String>("X-Aleo-Casing"))
+        .map(
+            |(integers, casing): (Option<String>, Option<String>),
+             integers_header: Option<String>,
+             casing_header: Option<String>| ResponseFormat {
+                integers: match integers.or(integers_header).as_deref() {
+                    Some("string") => IntegerMode::String,
+                    _ => IntegerMode::Number,
+                },
+                casing: match casing.or(casing_header).as_deref() {
+                    Some("camel") => FieldCasing::Camel,
+                    _ => FieldCasing::Snake,
+                },
+            },
+        )
+}
+
+/// Returns a JSON reply for the given value, serialized in the given format.
+fn json<T: Serialize>(value: &T, format: ResponseFormat) -> reply::Json {
+    formatted_json(value, format, false)
+}
+
+/// Returns a JSON reply for the given response struct, or list of response structs,
+/// serialized in the given format with the field aliases.
+fn aliased_json<T: Serialize>(value: &T, format: ResponseFormat) -> reply::Json {
+    formatted_json(value, format, true)
+}
+
+/// Returns a JSON reply for the given value, serialized in the given format.
+fn formatted_json<T: Serialize>(value: &T, format: ResponseFormat, aliased: bool) -> reply::Json {
+    if format.is_default() {
+        return reply::json(value);
+    }
+    match serde_json::to_value(value) {
+        Ok(mut value) => {
+            format.apply(&mut value, aliased);
+            reply::json(&value)
+        }
+        // Defer to the default reply, which reports the serialization error.
+        Err(_) => reply::json(value),
+    }
+}
+
+/// Deserializes an integer that may have been serialized as a JSON number or as a string.
+fn deserialize_integer<'de, D: serde::Deserializer<'de>, T: serde::de::DeserializeOwned + FromStr>(
+    deserializer: D,
+) -> Result<T, D::Error>
+where
+    <T as FromStr>::Err: core::fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Integer<T> {
+        Number(T),
+        String(String),
+    }
+
+    match Integer::<T>::deserialize(deserializer)? {
+        Integer::Number(integer) => Ok(integer),
+        Integer::String(string) => string.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+/// A request to update the node settings, where omitted settings are left unchanged.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct NodeSettingsUpdate {
+    /// The minimum interval between produced blocks, in milliseconds.
+    pub block_interval_ms: Option<u64>,
+    /// The log level [options: 0, 1, 2, 3].
+    pub log_level: Option<u8>,
+    /// The maximum number of broadcast transactions per block; zero means no limit.
+    pub max_block_transactions: Option<usize>,
+    /// The maximum number of pending broadcast transactions per sender; zero means no limit.
+    pub max_pending_per_sender: Option<usize>,
+    /// The maximum number of pending broadcast transactions; zero means no limit.
+    pub max_pending_transactions: Option<usize>,
+    /// The maximum total size of the pending broadcast transactions, in bytes; zero means no limit.
+    pub max_pending_bytes: Option<usize>,
+    /// The time after which a pending broadcast transaction is evicted, in seconds; zero means never.
+    pub pending_ttl_secs: Option<u64>,
+}
+
 /// Shorthand for the parent half of the `Ledger` message channel.
 pub type LedgerSender<N> = mpsc::Sender<LedgerRequest<N>>;
 /// Shorthand for the child half of the `Ledger` message channel.
@@ -42,234 +605,1810 @@ pub type LedgerReceiver<N> = mpsc::Receiver<LedgerRequest<N>>;
 /// An enum of requests that the `Ledger` struct processes.
 #[derive(Debug)]
 pub enum LedgerRequest<N: Network> {
-    TransactionBroadcast(Transaction<N>),
+    /// A broadcast transaction, and its sender.
+    TransactionBroadcast(Transaction<N>, String),
+}
+
+/// The health of the node, in a schema shared by the nodes of a fleet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Health {
+    /// The health status.
+    pub status: HealthStatus,
+    /// The version of the node.
+    pub version: String,
+    /// The network ID of the node.
+    pub network: u16,
+    /// The number of seconds since the node started.
+    pub uptime_secs: u64,
+    /// The latest block height of the node.
+    pub latest_height: u32,
+    /// The time the latest block was produced, in seconds since the UNIX epoch.
+    pub last_block_time: u64,
+    /// The number of seconds since the latest block was produced.
+    pub last_block_age_secs: u64,
+}
+
+impl Health {
+    /// The number of block intervals without a block after which the node is degraded.
+    const STALE_BLOCK_INTERVALS: u32 = 3;
+}
+
+/// The health status of the node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// The node is serving requests and producing blocks.
+    Ok,
+    /// The node is serving requests, but has not produced a block for several block intervals.
+    Degraded,
+    /// The node is shutting down.
+    Unavailable,
+}
+
+/// The node information, used to check compatibility before talking to a node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct NodeInfo {
+    /// The version of the node.
+    pub version: String,
+    /// The network ID of the node.
+    #[serde(deserialize_with = "deserialize_integer")]
+    pub network: u16,
+    /// The latest block height of the node.
+    #[serde(deserialize_with = "deserialize_integer")]
+    pub latest_height: u32,
+    /// The features supported by the node.
+    pub features: Vec<String>,
+}
+
+impl NodeInfo {
+    /// The features supported by this node.
+    pub const FEATURES: &'static [&'static str] = &[
+        "address_rewards",
+        "blocks",
+        "blocks_range",
+        "blocks_subscribe",
+        "deploy",
+        "execute_dry_run",
+        "execution_verify",
+        "health",
+        "latest_summary",
+        "memory_pool",
+        "memory_pool_transactions",
+        "metrics",
+        "producer_status",
+        "program_abi",
+        "record",
+        "records",
+        "records_ciphertexts",
+        "spending_transaction",
+        "state_path",
+        "state_root",
+        "subscriptions",
+        "supply",
+        "transaction",
+        "transaction_broadcast",
+        "transaction_status",
+        "transaction_upload",
+        "transition",
+    ];
+
+    /// Ensures the node is compatible with this version of Aleo on the given network.
+    pub fn check_compatibility<N: Network>(&self) -> Result<()> {
+        ensure!(
+            self.network == N::ID,
+            "The node is on network {}, but the expected network is {}",
+            self.network,
+            N::ID
+        );
+        // Versions are compatible if their major and minor components match.
+        let major_minor = |version: &str| version.splitn(3, '.').take(2).collect::<Vec<_>>();
+        ensure!(
+            major_minor(&self.version) == major_minor(env!("CARGO_PKG_VERSION")),
+            "The node is running version {}, which is incompatible with version {}",
+            self.version,
+            env!("CARGO_PKG_VERSION")
+        );
+        Ok(())
+    }
+}
+
+/// The configuration of the server.
+#[derive(Clone, Debug, Default)]
+pub struct ServerConfig {
+    /// If `true`, the records endpoints only return the record commitments.
+    pub redact_records: bool,
+    /// The API keys and their roles. If empty, all requests are authorized.
+    pub api_keys: IndexMap<String, Role>,
+    /// If `true`, the read-only endpoints do not require an API key.
+    pub public_reads: bool,
+    /// The file of additional API keys, which is reloaded whenever it changes.
+    pub api_keys_file: Option<PathBuf>,
+    /// The file to write the startup report to, once the server is ready.
+    pub ready_file: Option<PathBuf>,
+    /// If `true`, faults can be injected into the requests via the admin endpoints.
+    pub fault_injection: bool,
+    /// The window in which a rebroadcast transaction is rejected as a replay; zero disables it.
+    pub replay_window: Duration,
+    /// The number of transactions to verify concurrently; zero is treated as one.
+    pub verification_threads: usize,
+    /// The maximum size of a transaction body, in bytes; zero uses the default.
+    pub max_body_size: u64,
+    /// The number of requests per second allowed from each IP address; zero disables rate limiting.
+    pub rate_limit: f64,
+    /// The number of requests an IP address may burst above the rate limit; zero allows one second worth.
+    pub rate_limit_burst: u32,
+    /// The IP address to bind the server to [default: 0.0.0.0].
+    pub addr: Option<IpAddr>,
+    /// The port to bind the server to [default: 4180], where zero picks a free port.
+    pub port: Option<u16>,
+    /// The certificate and key to serve HTTPS with, instead of HTTP.
+    pub tls: Option<TlsConfig>,
+    /// The OTLP collector to export the traces to, which requires the `telemetry` feature.
+    pub otlp_endpoint: Option<String>,
+}
+
+/// The TLS configuration of the server.
+#[derive(Clone, Debug)]
+pub struct TlsConfig {
+    /// The path to the PEM-encoded certificate chain.
+    pub cert: PathBuf,
+    /// The path to the PEM-encoded private key.
+    pub key: PathBuf,
+}
+
+impl ServerConfig {
+    /// The interval at which the API keys file is checked for changes.
+    const API_KEYS_FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+    /// The default IP address to bind the server to.
+    pub const DEFAULT_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+    /// The default maximum size of a transaction body, in bytes.
+    pub const DEFAULT_MAX_BODY_SIZE: u64 = 10 * 1024 * 1024;
+    /// The default port to bind the server to.
+    pub const DEFAULT_PORT: u16 = 4180;
+
+    /// Returns the socket address to bind the server to.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(
+            self.addr.unwrap_or(Self::DEFAULT_ADDR),
+            self.port.unwrap_or(Self::DEFAULT_PORT),
+        )
+    }
+
+    /// Returns the maximum size of a transaction body, in bytes.
+    pub fn max_body_size(&self) -> u64 {
+        match self.max_body_size {
+            0 => Self::DEFAULT_MAX_BODY_SIZE,
+            max_body_size => max_body_size,
+        }
+    }
+
+    /// Parses an API key and its role from `<KEY>=<ROLE>`.
+    pub fn parse_api_key(api_key: &str) -> Result<(String, Role)> {
+        let (key, role) = api_key
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid API key '{api_key}', expected '<KEY>=<ROLE>'"))?;
+        Ok((key.trim().to_string(), role.trim().parse()?))
+    }
+
+    /// Loads the API keys from the given file, which contains one `<KEY>=<ROLE>` entry per line.
+    pub fn load_api_keys(path: &Path) -> Result<IndexMap<String, Role>> {
+        std::fs::read_to_string(path)?
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(Self::parse_api_key)
+            .collect()
+    }
+
+    /// Returns `Ok(())` if the given API key grants the given role.
+    fn authorize(&self, api_key: Option<&str>, role: Role) -> Result<(), ServerError> {
+        // If there are no API keys, or the endpoint is public, authorize the request.
+        // The admin endpoints always require an API key.
+        if (self.api_keys.is_empty() && role != Role::Admin) || (role == Role::ReadOnly && self.public_reads) {
+            return Ok(());
+        }
+        match api_key.and_then(|api_key| self.api_keys.get(api_key)) {
+            Some(granted) if *granted >= role => Ok(()),
+            Some(_) => Err(ServerError::Forbidden),
+            None => Err(ServerError::Unauthorized),
+        }
+    }
+}
+
+/// The startup report of the server, written to the ready file once the server is ready.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartupReport {
+    /// The version of the node.
+    pub version: String,
+    /// The network ID of the node.
+    pub network: u16,
+    /// The address the server is bound to.
+    pub address: SocketAddr,
+    /// The storage of the ledger.
+    pub storage: String,
+    /// The genesis block hash.
+    pub genesis_hash: String,
+    /// If `true`, the server is served over HTTPS.
+    #[serde(default)]
+    pub tls: bool,
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let scheme = if self.tls { "https" } else { "http" };
+        writeln!(f, "🌐 Server is running at {scheme}://{}\n", self.address)?;
+        writeln!(f, " {:>12}  {}", "Version".cyan().bold(), self.version)?;
+        writeln!(f, " {:>12}  {}", "Network".cyan().bold(), self.network)?;
+        writeln!(f, " {:>12}  {}", "Storage".cyan().bold(), self.storage)?;
+        write!(f, " {:>12}  {}", "Genesis".cyan().bold(), self.genesis_hash)
+    }
 }
 
 /// A server for the ledger.
-#[allow(dead_code)]
 #[derive(Debug)]
 pub struct Server<N: Network> {
     /// The runtime.
     runtime: tokio::runtime::Runtime,
-    /// The ledger sender.
-    ledger_sender: LedgerSender<N>,
-    /// The server handles.
-    handles: Vec<JoinHandle<()>>,
+    /// The ledger sender, which is dropped on shutdown to close the ledger channel.
+    ledger_sender: Mutex<Option<LedgerSender<N>>>,
+    /// The sender of the shutdown signal.
+    shutdown_sender: watch::Sender<bool>,
+    /// The handles of the tasks that are joined on shutdown.
+    handles: Mutex<Vec<JoinHandle<()>>>,
+    /// The handles of the tasks that only wait for events, which are aborted on shutdown.
+    listeners: Mutex<Vec<JoinHandle<()>>>,
     /// PhantomData.
     _phantom: PhantomData<N>,
 }
 
 impl<N: Network> Server<N> {
+    /// The current API version, used as the route prefix.
+    const API_VERSION: &'static str = "v1";
+    /// The name of the response header containing the API version.
+    const API_VERSION_HEADER: &'static str = "X-Aleo-API-Version";
+    /// The maximum number of broadcast transactions waiting in the ledger queue.
+    const LEDGER_QUEUE_CAPACITY: usize = 64;
+    /// The maximum number of blocks returned by a block range request.
+    const MAX_BLOCKS_PER_REQUEST: u32 = 50;
+
     /// Initializes a new instance of the server.
-    pub fn start(ledger: Arc<Ledger<N>>) -> Result<Self> {
+    pub fn start(ledger: Arc<Ledger<N>>, config: ServerConfig) -> Result<Self> {
+        // Share the configuration with the routes.
+        let redact_records = config.redact_records;
+        let config = Arc::new(RwLock::new(config));
         // Initialize a channel to send requests to the ledger.
-        let (ledger_sender, ledger_receiver) = mpsc::channel(64);
+        let (ledger_sender, ledger_receiver) = mpsc::channel(Self::LEDGER_QUEUE_CAPACITY);
+        // Initialize the verification workers.
+        let verification_threads = config.read().verification_threads.max(1);
+        let workers = Arc::new(Semaphore::new(verification_threads));
+        // Initialize the shutdown signal.
+        let (shutdown_sender, mut shutdown_receiver) = watch::channel(false);
+
+        // Prefix the routes with the network name.
+        let network = SupportedNetwork::from_id(N::ID)?.name();
+
+        // GET /health
+        let health = warp::get()
+            .and(warp::path!("health"))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::health);
+
+        // GET /{network}/node/info
+        let node_info = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("node" / "info"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::node_info);
+
+        // GET /{network}/blocks?start={start}&end={end}
+        let get_blocks = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("blocks"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::query::<BlocksRangeQuery>())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::get_blocks);
+
+        // GET /{network}/blocks/subscribe
+        let blocks_subscribe = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("blocks" / "subscribe"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::query::<BlocksQuery>())
+            .and(warp::ws())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::blocks_subscribe);
+
+        // GET /{network}/transaction/{id}/status
+        let transaction_status = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("transaction" / N::TransactionID / "status"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::transaction_status);
+
+        // GET /{network}/transaction/{id}
+        let get_transaction = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("transaction" / N::TransactionID))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::get_transaction);
+
+        // GET /{network}/transition/{id}
+        let get_transition = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("transition" / N::TransitionID))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::get_transition);
+
+        // GET /{network}/memoryPool
+        let memory_pool = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("memoryPool"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::memory_pool);
+
+        // GET /{network}/memoryPool/evictions
+        let memory_pool_evictions = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("memoryPool" / "evictions"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::memory_pool_evictions);
 
-        // GET /testnet3/latest/height
+        // GET /{network}/memoryPool/transactions
+        let memory_pool_transactions = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("memoryPool" / "transactions"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::memory_pool_transactions);
+
+        // GET /{network}/memoryPool/transaction/{id}
+        let memory_pool_transaction = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("memoryPool" / "transaction" / N::TransactionID))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::memory_pool_transaction);
+
+        // GET /{network}/latest/height
         let latest_height = warp::get()
-            .and(warp::path!("testnet3" / "latest" / "height"))
+            .and(warp::path(network))
+            .and(warp::path!("latest" / "height"))
+            .and(authorize(config.clone(), Role::ReadOnly))
             .and(with(ledger.clone()))
+            .and(response_format())
             .and_then(Self::latest_height);
 
-        // GET /testnet3/latest/hash
+        // GET /{network}/latest/hash
         let latest_hash = warp::get()
-            .and(warp::path!("testnet3" / "latest" / "hash"))
+            .and(warp::path(network))
+            .and(warp::path!("latest" / "hash"))
+            .and(authorize(config.clone(), Role::ReadOnly))
             .and(with(ledger.clone()))
+            .and(response_format())
             .and_then(Self::latest_hash);
 
-        // GET /testnet3/latest/block
+        // GET /{network}/latest/summary
+        let latest_summary = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("latest" / "summary"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::latest_summary);
+
+        // GET /{network}/latest/stateRoot
+        let latest_state_root = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("latest" / "stateRoot"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::latest_state_root);
+
+        // GET /{network}/stateRoot/{height}
+        let state_root = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("stateRoot" / u32))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::state_root);
+
+        // GET /{network}/latest/block
         let latest_block = warp::get()
-            .and(warp::path!("testnet3" / "latest" / "block"))
+            .and(warp::path(network))
+            .and(warp::path!("latest" / "block"))
+            .and(authorize(config.clone(), Role::ReadOnly))
             .and(with(ledger.clone()))
+            .and(response_format())
             .and_then(Self::latest_block);
 
-        // GET /testnet3/block/{height}
+        // GET /{network}/block/{height}
         let get_block = warp::get()
-            .and(warp::path!("testnet3" / "block" / u32))
+            .and(warp::path(network))
+            .and(warp::path!("block" / u32))
+            .and(authorize(config.clone(), Role::ReadOnly))
             .and(with(ledger.clone()))
+            .and(response_format())
             .and_then(Self::get_block);
 
-        // GET /testnet3/statePath/{commitment}
+        // GET /{network}/record/{commitment}
+        let get_record = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("record" / Field<N>))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::query::<RecordQuery>())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::get_record);
+
+        // GET /{network}/find/spendingTransaction/{serial_number}
+        let find_spending_transaction = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("find" / "spendingTransaction" / Field<N>))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::find_spending_transaction);
+
+        // GET /{network}/supply
+        let supply = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("supply"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::supply);
+
+        // GET /{network}/address/{address}/rewards
+        let address_rewards = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("address" / Address<N> / "rewards"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::address_rewards);
+
+        // GET /{network}/program/{id}/abi
+        let program_abi = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("program" / ProgramID<N> / "abi"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::program_abi);
+
+        // GET /{network}/program/{id}/available
+        let program_available = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("program" / ProgramID<N> / "available"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and_then(Self::program_available);
+
+        // GET /{network}/producer/status
+        let producer_status = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("producer" / "status"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::producer_status);
+
+        // GET /{network}/statePath/{commitment}
         let state_path = warp::get()
-            .and(warp::path!("testnet3" / "statePath"))
+            .and(warp::path(network))
+            .and(warp::path!("statePath"))
+            .and(authorize(config.clone(), Role::ReadOnly))
             .and(warp::body::content_length_limit(128))
             .and(warp::body::json())
             .and(with(ledger.clone()))
+            .and(response_format())
             .and_then(Self::state_path);
 
-        // GET /testnet3/records/all
+        // GET /{network}/records/ciphertexts?start={start}&end={end}
+        let records_ciphertexts = warp::get()
+            .and(warp::path(network))
+            .and(warp::path!("records" / "ciphertexts"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::query::<BlocksRangeQuery>())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::records_ciphertexts);
+
+        // GET /{network}/records/all
         let records_all = warp::get()
-            .and(warp::path!("testnet3" / "records" / "all"))
-            .and(warp::body::content_length_limit(256))
+            .and(warp::path(network))
+            .and(warp::path!("records" / "all"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(1024))
             .and(warp::body::json())
             .and(with(ledger.clone()))
+            .and(with(redact_records))
+            .and(response_format())
             .and_then(Self::records_all);
 
-        // GET /testnet3/records/spent
+        // GET /{network}/records/spent
         let records_spent = warp::get()
-            .and(warp::path!("testnet3" / "records" / "spent"))
-            .and(warp::body::content_length_limit(256))
+            .and(warp::path(network))
+            .and(warp::path!("records" / "spent"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(1024))
             .and(warp::body::json())
             .and(with(ledger.clone()))
+            .and(with(redact_records))
+            .and(response_format())
             .and_then(Self::records_spent);
 
-        // GET /testnet3/records/unspent
+        // GET /{network}/records/unspent
         let records_unspent = warp::get()
-            .and(warp::path!("testnet3" / "records" / "unspent"))
-            .and(warp::body::content_length_limit(128))
+            .and(warp::path(network))
+            .and(warp::path!("records" / "unspent"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(1024))
             .and(warp::body::json())
             .and(with(ledger.clone()))
+            .and(with(redact_records))
+            .and(response_format())
             .and_then(Self::records_unspent);
 
-        // POST /testnet3/transaction/broadcast
+        // Initialize the window of recently broadcast transactions.
+        let replay_window = Arc::new(ReplayWindow::new(config.read().replay_window));
+        let max_body_size = config.read().max_body_size();
+
+        // POST /{network}/transaction/broadcast
         let transaction_broadcast = warp::post()
-            .and(warp::path!("testnet3" / "transaction" / "broadcast"))
-            .and(warp::body::content_length_limit(10 * 1024 * 1024))
+            .and(warp::path(network))
+            .and(warp::path!("transaction" / "broadcast"))
+            .and(authorize(config.clone(), Role::Broadcast))
+            .and(warp::body::content_length_limit(max_body_size))
             .and(warp::body::json())
+            .and(warp::addr::remote())
             .and(with(ledger_sender.clone()))
+            .and(with(replay_window.clone()))
+            .and(with(ledger.clone()))
             .and_then(Self::transaction_broadcast);
 
+        // POST /{network}/transaction/upload
+        let transaction_upload = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("transaction" / "upload"))
+            .and(authorize(config.clone(), Role::Broadcast))
+            .and(warp::header::exact_ignore_case(
+                "content-type",
+                "application/octet-stream",
+            ))
+            .and(warp::body::content_length_limit(max_body_size))
+            .and(warp::body::bytes())
+            .and_then(
+                |bytes: warp::hyper::body::Bytes| async move { Transaction::<N>::from_bytes_le(&bytes).or_reject() },
+            )
+            .and(warp::addr::remote())
+            .and(with(ledger_sender.clone()))
+            .and(with(replay_window.clone()))
+            .and(with(ledger.clone()))
+            .and_then(Self::transaction_broadcast);
+
+        // POST /{network}/deploy
+        let deploy = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("deploy"))
+            .and(authorize(config.clone(), Role::Broadcast))
+            .and(warp::body::content_length_limit(max_body_size))
+            .and(warp::body::json())
+            .and(warp::addr::remote())
+            .and(with(ledger_sender.clone()))
+            .and(with(replay_window.clone()))
+            .and(with(ledger.clone()))
+            .and_then(Self::deploy);
+
+        // POST /{network}/payment/fulfill
+        let payment_fulfill = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("payment" / "fulfill"))
+            .and(authorize(config.clone(), Role::Admin))
+            .and(warp::body::content_length_limit(4096))
+            .and(warp::body::json())
+            .and(warp::addr::remote())
+            .and(with(ledger_sender.clone()))
+            .and(with(replay_window))
+            .and(with(ledger.clone()))
+            .and_then(Self::payment_fulfill);
+
+        // POST /{network}/payment/verify
+        let payment_verify = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("payment" / "verify"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(4096))
+            .and(warp::body::json())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::payment_verify);
+
+        // POST /{network}/execution/verify
+        let execution_verify = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("execution" / "verify"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(max_body_size))
+            .and(warp::body::json())
+            .and(with(ledger.clone()))
+            .and_then(Self::execution_verify);
+
+        // POST /{network}/execute/dryRun
+        let execute_dry_run = warp::post()
+            .and(warp::path(network))
+            .and(warp::path!("execute" / "dryRun"))
+            .and(authorize(config.clone(), Role::Broadcast))
+            .and(warp::body::content_length_limit(max_body_size))
+            .and(warp::body::json())
+            .and(with(ledger.clone()))
+            .and(response_format())
+            .and_then(Self::execute_dry_run);
+
+        // POST /admin/config
+        let admin_config = warp::post()
+            .and(warp::path!("admin" / "config"))
+            .and(authorize(config.clone(), Role::Admin))
+            .and(warp::body::content_length_limit(1024))
+            .and(warp::body::json())
+            .and(with(ledger.clone()))
+            .and_then(Self::admin_config);
+
+        // POST /admin/loglevel
+        let admin_log_level = warp::post()
+            .and(warp::path!("admin" / "loglevel"))
+            .and(authorize(config.clone(), Role::Admin))
+            .and(warp::body::content_length_limit(16))
+            .and(warp::body::json())
+            .and(with(ledger.clone()))
+            .and_then(Self::admin_log_level);
+
+        // Initialize the faults to inject.
+        let faults: Faults = Default::default();
+
+        // Initialize the rate limiter.
+        let limiter = {
+            let config = config.read();
+            match config.rate_limit > 0.0 {
+                true => Some(Arc::new(RateLimiter::new(config.rate_limit, config.rate_limit_burst))),
+                false => None,
+            }
+        };
+        let rate_limit = rate_limit(limiter, ledger.clone());
+
+        // POST /admin/faults
+        let admin_faults = warp::post()
+            .and(warp::path!("admin" / "faults"))
+            .and(authorize(config.clone(), Role::Admin))
+            .and(warp::body::content_length_limit(16 * 1024))
+            .and(warp::body::json())
+            .and(with(config.read().fault_injection))
+            .and(with(faults.clone()))
+            .and_then(Self::admin_faults);
+
+        // Initialize the subscriptions.
+        let subscriptions: Subscriptions<N> = Default::default();
+
+        // POST /subscriptions
+        let subscription_register = warp::post()
+            .and(warp::path!("subscriptions"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(warp::body::content_length_limit(256))
+            .and(warp::body::json())
+            .and(with(subscriptions.clone()))
+            .and_then(Self::subscription_register);
+
+        // DELETE /subscriptions/{id}
+        let subscription_unregister = warp::delete()
+            .and(warp::path!("subscriptions" / String))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(subscriptions.clone()))
+            .and_then(Self::subscription_unregister);
+
+        // GET /debug
+        let debug = warp::get()
+            .and(warp::path!("debug"))
+            .and(authorize(config.clone(), Role::Admin))
+            .and(with(ledger.clone()))
+            .and(with(ledger_sender.clone()))
+            .and(with(workers.clone()))
+            .and(with(verification_threads))
+            .and(with(subscriptions.clone()))
+            .and_then(Self::debug);
+
+        // GET /metrics
+        let metrics = warp::get()
+            .and(warp::path!("metrics"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(metrics_handle()?))
+            .and_then(Self::metrics);
+
+        // GET /subscriptions/{id}/records
+        let subscription_records = warp::get()
+            .and(warp::path!("subscriptions" / String / "records"))
+            .and(authorize(config.clone(), Role::ReadOnly))
+            .and(with(subscriptions))
+            .and(with(ledger.clone()))
+            .and(with(redact_records))
+            .and(with(shutdown_receiver.clone()))
+            .and_then(Self::subscription_records);
+
+        // Ensure the TLS files are readable, as the TLS server panics on bind otherwise.
+        let tls = config.read().tls.clone();
+        if let Some(tls) = &tls {
+            for path in [&tls.cert, &tls.key] {
+                std::fs::metadata(path).map_err(|error| anyhow!("Failed to read {}: {error}", path.display()))?;
+            }
+        }
+
+        // Prepare the startup report.
+        let ready_file = config.read().ready_file.clone();
+        let mut report = StartupReport {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            network: N::ID,
+            address: config.read().socket_addr(),
+            tls: tls.is_some(),
+            storage: "memory".to_string(),
+            genesis_hash: ledger.ledger.read().get_block(0)?.hash().to_string(),
+        };
+
         // Initialize a runtime.
         let runtime = tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .thread_stack_size(8 * 1024 * 1024)
             .build()?;
 
+        // Start exporting the traces.
+        if let Some(endpoint) = config.read().otlp_endpoint.clone() {
+            #[cfg(feature = "telemetry")]
+            {
+                let _guard = runtime.enter();
+                crate::helpers::start_telemetry(&endpoint)?;
+            }
+            #[cfg(not(feature = "telemetry"))]
+            anyhow::bail!("Exporting traces to {endpoint} requires the 'telemetry' feature");
+        }
+
         // Initialize the server.
-        let handles = runtime.block_on(async move {
-            // Initialize a vector for the server handles.
+        let (handles, listeners) = runtime.block_on(async move {
+            // Initialize the vectors for the server handles.
             let mut handles = Vec::new();
+            let mut listeners = Vec::new();
 
             // Spawn the server.
             handles.push(tokio::spawn(async move {
                 // Prepare the list of routes.
-                let routes = latest_height
+                let routes = health
+                    .or(node_info)
+                    .or(latest_height)
                     .or(latest_hash)
                     .or(latest_block)
+                    .or(latest_summary)
+                    .or(latest_state_root)
+                    .or(state_root)
                     .or(get_block)
+                    .or(get_blocks)
+                    .or(blocks_subscribe)
+                    .or(get_record)
+                    .or(address_rewards)
+                    .or(producer_status)
+                    .or(program_abi)
+                    .or(program_available)
+                    .or(supply)
+                    .or(find_spending_transaction)
                     .or(state_path)
+                    .or(records_ciphertexts)
                     .or(records_all)
                     .or(records_spent)
                     .or(records_unspent)
-                    .or(transaction_broadcast);
+                    .or(transaction_broadcast)
+                    .or(transaction_upload)
+                    .or(deploy)
+                    .or(execute_dry_run)
+                    .or(execution_verify)
+                    .or(payment_fulfill)
+                    .or(payment_verify)
+                    .or(transaction_status)
+                    .or(get_transaction)
+                    .or(get_transition)
+                    .or(memory_pool)
+                    .or(memory_pool_evictions)
+                    .or(memory_pool_transactions)
+                    .or(memory_pool_transaction)
+                    .or(subscription_register)
+                    .or(subscription_unregister)
+                    .or(subscription_records)
+                    .or(admin_config)
+                    .or(admin_log_level)
+                    .or(admin_faults)
+                    .or(debug)
+                    .or(metrics);
+                // Serve the routes under the API version prefix, keeping the unversioned paths as legacy aliases.
+                let routes = warp::path(Self::API_VERSION).and(routes.clone()).or(routes);
+                // Rate limit the requests, and inject the configured faults.
+                let routes = rate_limit
+                    .and(inject_faults(faults))
+                    .and(routes)
+                    .with(reply::with::header(Self::API_VERSION_HEADER, Self::API_VERSION))
+                    .with(warp::trace::request())
+                    .recover(handle_rejection)
+                    .with(warp::log::custom(record_request));
+                // Stop accepting connections once the shutdown is signalled.
+                let signal = async move {
+                    let _ = shutdown_receiver.changed().await;
+                };
+                // Bind the server.
+                let (address, server): (_, Pin<Box<dyn Future<Output = ()> + Send>>) = match tls {
+                    Some(tls) => {
+                        let (address, server) = warp::serve(routes)
+                            .tls()
+                            .cert_path(&tls.cert)
+                            .key_path(&tls.key)
+                            .bind_with_graceful_shutdown(report.address, signal);
+                        (address, Box::pin(server))
+                    }
+                    None => match warp::serve(routes).try_bind_with_graceful_shutdown(report.address, signal) {
+                        Ok((address, server)) => (address, Box::pin(server)),
+                        Err(error) => {
+                            eprintln!("Failed to bind the server to {}: {error}", report.address);
+                            return;
+                        }
+                    },
+                };
+                report.address = address;
+                // Report that the server is ready.
+                println!("\n{report}");
+                if let Some(path) = ready_file {
+                    if let Err(error) = Self::write_ready_file(&path, &report) {
+                        eprintln!("Failed to write the ready file to {}: {error}", path.display());
+                    }
+                }
+                if let Err(error) = notify("READY=1") {
+                    eprintln!("Failed to notify the service manager: {error}");
+                }
                 // Start the server.
-                println!("\n🌐 Server is running at http://0.0.0.0:4180");
-                warp::serve(routes).run(([0, 0, 0, 0], 4180)).await;
+                server.await;
             }));
 
+            // Spawn the signal handler.
+            listeners.push(Self::start_signal_handler(ledger.clone()));
+
             // Spawn the ledger handler.
-            handles.push(Self::start_handler(ledger, ledger_receiver));
+            handles.push(Self::start_handler(
+                ledger,
+                ledger_receiver,
+                workers,
+                verification_threads,
+            ));
+
+            // Spawn the API keys file watcher.
+            let api_keys_file = config.read().api_keys_file.clone();
+            if let Some(path) = api_keys_file {
+                listeners.push(Self::start_api_keys_watcher(config, path));
+            }
 
             // Return the handles.
-            handles
+            (handles, listeners)
         });
 
         Ok(Self {
             runtime,
-            ledger_sender,
-            handles,
+            ledger_sender: Mutex::new(Some(ledger_sender)),
+            shutdown_sender,
+            handles: Mutex::new(handles),
+            listeners: Mutex::new(listeners),
             _phantom: PhantomData,
         })
     }
 
-    /// Initializes a ledger handler.
-    fn start_handler(ledger: Arc<Ledger<N>>, mut ledger_receiver: LedgerReceiver<N>) -> JoinHandle<()> {
+    /// Shuts down the server gracefully: it stops accepting connections, lets the in-flight requests
+    /// finish, drains the queued transactions, and joins all of its tasks, which frees up its port.
+    /// Calling it again has no effect.
+    ///
+    /// This blocks the current thread, so it must not be called from within an async context.
+    pub fn shutdown(&self) {
+        // Signal the server to stop accepting connections.
+        let _ = self.shutdown_sender.send(true);
+        // Abort the tasks that only wait for events.
+        for listener in self.listeners.lock().drain(..) {
+            listener.abort();
+        }
+        // Drop the ledger sender, so the ledger channel closes once the server has stopped.
+        self.ledger_sender.lock().take();
+        // Join the remaining tasks, in the order they were spawned.
+        let handles = std::mem::take(&mut *self.handles.lock());
+        self.runtime.block_on(async move {
+            for handle in handles {
+                if let Err(error) = handle.await {
+                    eprintln!("Failed to join a server task: {error}");
+                }
+            }
+        });
+    }
+
+    /// Writes the startup report to the given file, via a temporary file so it appears atomically.
+    fn write_ready_file(path: &Path, report: &StartupReport) -> Result<()> {
+        let temporary_path = path.with_extension("tmp");
+        std::fs::write(&temporary_path, serde_json::to_string_pretty(report)?)?;
+        std::fs::rename(&temporary_path, path)?;
+        Ok(())
+    }
+
+    /// Initializes a watcher that reloads the API keys whenever the given file changes.
+    /// The API keys from the initial configuration are always retained.
+    fn start_api_keys_watcher(config: Arc<RwLock<ServerConfig>>, path: PathBuf) -> JoinHandle<()> {
+        let initial_api_keys = config.read().api_keys.clone();
+        tokio::spawn(async move {
+            let mut last_modified = None;
+            loop {
+                if let Ok(modified) = std::fs::metadata(&path).and_then(|metadata| metadata.modified()) {
+                    if last_modified != Some(modified) {
+                        match ServerConfig::load_api_keys(&path) {
+                            Ok(api_keys) => {
+                                let mut all_api_keys = initial_api_keys.clone();
+                                all_api_keys.extend(api_keys);
+                                config.write().api_keys = all_api_keys;
+                                last_modified = Some(modified);
+                            }
+                            Err(error) => eprintln!("Failed to reload the API keys from {}: {error}", path.display()),
+                        }
+                    }
+                }
+                tokio::time::sleep(ServerConfig::API_KEYS_FILE_POLL_INTERVAL).await;
+            }
+        })
+    }
+
+    /// Initializes a handler that signals the ledger to shut down on `SIGTERM` (or `Ctrl-C` on other platforms).
+    fn start_signal_handler(ledger: Arc<Ledger<N>>) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            {
+                use tokio::signal::unix::{signal, SignalKind};
+                match signal(SignalKind::terminate()) {
+                    Ok(mut terminate) => {
+                        terminate.recv().await;
+                    }
+                    Err(error) => {
+                        eprintln!("Failed to listen for SIGTERM: {error}");
+                        return;
+                    }
+                }
+            }
+            #[cfg(not(unix))]
+            if let Err(error) = tokio::signal::ctrl_c().await {
+                eprintln!("Failed to listen for Ctrl-C: {error}");
+                return;
+            }
+            ledger.shut_down();
+        })
+    }
+
+    /// Initializes a ledger handler, which verifies the transactions on a bounded worker pool
+    /// so that a large execution does not hold up the transactions behind it.
+    fn start_handler(
+        ledger: Arc<Ledger<N>>,
+        mut ledger_receiver: LedgerReceiver<N>,
+        workers: Arc<Semaphore>,
+        verification_threads: usize,
+    ) -> JoinHandle<()> {
         tokio::spawn(async move {
             while let Some(request) = ledger_receiver.recv().await {
                 match request {
-                    LedgerRequest::TransactionBroadcast(transaction) => {
-                        if let Err(error) = ledger.add_to_memory_pool(transaction) {
-                            eprintln!("{error}")
-                        }
+                    LedgerRequest::TransactionBroadcast(transaction, sender) => {
+                        // Wait for a free worker.
+                        let permit = match workers.clone().acquire_owned().await {
+                            Ok(permit) => permit,
+                            Err(_) => break,
+                        };
+                        // Verify the transaction and queue it in the memory pool.
+                        let ledger = ledger.clone();
+                        tokio::task::spawn_blocking(move || {
+                            if let Err(error) = ledger.queue_transaction(transaction, &sender) {
+                                eprintln!("{error}")
+                            }
+                            drop(permit);
+                        });
                     }
                 };
             }
+            // Wait for the in-flight verifications to finish.
+            let _ = workers.acquire_many(verification_threads as u32).await;
         })
     }
 }
 
 impl<N: Network> Server<N> {
+    /// Returns the node information.
+    async fn node_info(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(
+            &NodeInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                network: N::ID,
+                latest_height: ledger.ledger.read().latest_height(),
+                features: NodeInfo::FEATURES.iter().map(|feature| feature.to_string()).collect(),
+            },
+            format,
+        ))
+    }
+
+    /// Returns the blocks in the given range, capped at `MAX_BLOCKS_PER_REQUEST`.
+    async fn get_blocks(
+        query: BlocksRangeQuery,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        Self::check_blocks_range(&query)?;
+        // Fetch the blocks, up to the latest height.
+        let ledger = ledger.ledger.read();
+        let end = query.end.min(ledger.latest_height().saturating_add(1));
+        let blocks = (query.start..end)
+            .map(|height| ledger.get_block(height))
+            .collect::<Result<Vec<_>>>()
+            .or_reject()?;
+        Ok(json(&blocks, format))
+    }
+
+    /// Ensures the given block range is valid.
+    fn check_blocks_range(query: &BlocksRangeQuery) -> Result<(), Rejection> {
+        if query.start > query.end || query.end - query.start > Self::MAX_BLOCKS_PER_REQUEST {
+            return Err(reject::custom(ServerError::Request(format!(
+                "Invalid block range {}..{} (at most {} blocks per request)",
+                query.start,
+                query.end,
+                Self::MAX_BLOCKS_PER_REQUEST
+            ))));
+        }
+        Ok(())
+    }
+
+    /// Returns the record ciphertexts created in the given block range, to scan for records without a view key.
+    async fn records_ciphertexts(
+        query: BlocksRangeQuery,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        Self::check_blocks_range(&query)?;
+        let ciphertexts = ledger.find_record_ciphertexts(query.start..query.end).or_reject()?;
+        Ok(aliased_json(&ciphertexts, format))
+    }
+
+    /// Upgrades the connection to a WebSocket that receives each new block, or only its header.
+    async fn blocks_subscribe(
+        query: BlocksQuery,
+        ws: Ws,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        let header_only = query.header.unwrap_or(false);
+        Ok(ws.on_upgrade(move |socket| Self::send_blocks(socket, ledger, header_only, format)))
+    }
+
+    /// Sends each new block to the WebSocket, until the client disconnects or the ledger stops.
+    async fn send_blocks(mut socket: WebSocket, ledger: Arc<Ledger<N>>, header_only: bool, format: ResponseFormat) {
+        let mut blocks = ledger.subscribe_blocks();
+        loop {
+            // Wait for the next block; a lagging client only misses the skipped blocks.
+            let block = match blocks.recv().await {
+                Ok(block) => block,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            };
+            // Serialize the block or its header.
+            let value = match header_only {
+                true => serde_json::to_value(block.header()),
+                false => serde_json::to_value(&block),
+            };
+            let mut value = match value {
+                Ok(value) => value,
+                Err(error) => {
+                    eprintln!("Failed to serialize block {}: {error}", block.height());
+                    continue;
+                }
+            };
+            format.apply(&mut value, false);
+            // Send the block, and stop once the client is gone.
+            if socket.send(Message::text(value.to_string())).await.is_err() {
+                break;
+            }
+        }
+        let _ = socket.close().await;
+    }
+
+    /// Returns the record with the given commitment and its origin.
+    async fn get_record(
+        commitment: Field<N>,
+        query: RecordQuery,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        // Parse the serial number, if given.
+        let serial_number = query
+            .serial_number
+            .as_deref()
+            .map(Field::<N>::from_str)
+            .transpose()
+            .or_reject()?;
+        // Find the record.
+        match ledger.find_record(&commitment, serial_number.as_ref()).or_reject()? {
+            Some(record) => Ok(aliased_json(&record, format)),
+            None => Err(reject::not_found()),
+        }
+    }
+
+    /// Returns the credits supply of the ledger.
+    async fn supply(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(aliased_json(&ledger.supply().or_reject()?, format))
+    }
+
+    /// Returns the blocks produced by the given address, and the rewards credited for them.
+    async fn address_rewards(
+        address: Address<N>,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        Ok(aliased_json(&ledger.rewards(&address), format))
+    }
+
+    /// Returns the interface of the given program.
+    async fn program_abi(
+        program_id: ProgramID<N>,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        match ledger.find_program(&program_id).or_reject()? {
+            Some(program) => Ok(json(&ProgramAbi::from_program(&program).or_reject()?, format)),
+            None => Err(reject::custom(ServerError::NotFound(format!(
+                "Program '{program_id}' does not exist"
+            )))),
+        }
+    }
+
+    /// Returns `true` if the given program ID is not taken by a deployed program.
+    async fn program_available(program_id: ProgramID<N>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+        Ok(reply::json(&!ledger.contains_program(&program_id)))
+    }
+
+    /// Returns the recent block production of the node.
+    async fn producer_status(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(aliased_json(&ledger.producer_status(), format))
+    }
+
+    /// Returns the transaction that spent the record with the given serial number.
+    async fn find_spending_transaction(
+        serial_number: Field<N>,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        match ledger.find_spending_transaction(&serial_number).or_reject()? {
+            Some(transaction) => Ok(aliased_json(&transaction, format)),
+            None => Err(reject::not_found()),
+        }
+    }
+
+    /// Returns the status of the given transaction.
+    async fn transaction_status(
+        transaction_id: N::TransactionID,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        Ok(aliased_json(
+            &ledger.transaction_status(&transaction_id).or_reject()?,
+            format,
+        ))
+    }
+
+    /// Returns the confirmed transaction with the given ID.
+    async fn get_transaction(
+        transaction_id: N::TransactionID,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        match ledger.find_transaction(&transaction_id).or_reject()? {
+            Some(transaction) => Ok(json(&transaction, format)),
+            None => Err(reject::custom(ServerError::NotFound(format!(
+                "Transaction '{transaction_id}' does not exist"
+            )))),
+        }
+    }
+
+    /// Returns the confirmed transition with the given ID.
+    async fn get_transition(
+        transition_id: N::TransitionID,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        match ledger.find_transition(&transition_id).or_reject()? {
+            Some(transition) => Ok(json(&transition, format)),
+            None => Err(reject::custom(ServerError::NotFound(format!(
+                "Transition '{transition_id}' does not exist"
+            )))),
+        }
+    }
+
+    /// Returns the queued broadcast transactions, in the order they are selected into blocks.
+    async fn memory_pool(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.memory_pool_ordering(), format))
+    }
+
+    /// Returns the number of broadcast transactions evicted from the memory pool.
+    async fn memory_pool_evictions(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.memory_pool_evictions(), format))
+    }
+
+    /// Returns the broadcast transactions waiting in the memory pool.
+    async fn memory_pool_transactions(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.memory_pool_transactions(), format))
+    }
+
+    /// Returns the given broadcast transaction, if it is waiting in the memory pool.
+    async fn memory_pool_transaction(
+        transaction_id: N::TransactionID,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        match ledger.memory_pool_transaction(&transaction_id) {
+            Some(transaction) => Ok(json(&transaction, format)),
+            None => Err(reject::not_found()),
+        }
+    }
+
+    /// Returns the health of the node, with a 503 status code if it is unavailable.
+    async fn health(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        let now = ledger.clock().now();
+        let last_block_time = ledger.last_block_time();
+        let last_block_age = now.saturating_sub(last_block_time);
+        let block_interval = Duration::from_millis(ledger.settings().block_interval_ms);
+
+        let status = if ledger.is_shutting_down() {
+            HealthStatus::Unavailable
+        } else if !block_interval.is_zero() && last_block_age > block_interval * Health::STALE_BLOCK_INTERVALS {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Ok
+        };
+        let code = match status {
+            HealthStatus::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            HealthStatus::Ok | HealthStatus::Degraded => StatusCode::OK,
+        };
+
+        Ok(reply::with_status(
+            aliased_json(
+                &Health {
+                    status,
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                    network: N::ID,
+                    uptime_secs: ledger.uptime().as_secs(),
+                    latest_height: ledger.ledger.read().latest_height(),
+                    last_block_time: last_block_time.as_secs(),
+                    last_block_age_secs: last_block_age.as_secs(),
+                },
+                format,
+            ),
+            code,
+        ))
+    }
+
     /// Returns the latest block height.
-    async fn latest_height(ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.ledger.read().latest_height()))
+    async fn latest_height(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.ledger.read().latest_height(), format))
     }
 
     /// Returns the latest block hash.
-    async fn latest_hash(ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.ledger.read().latest_hash()))
+    async fn latest_hash(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.ledger.read().latest_hash(), format))
+    }
+
+    /// Returns the latest state root.
+    async fn latest_state_root(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&*ledger.ledger.read().latest_state_root(), format))
+    }
+
+    /// Returns the state root after the block at the given height.
+    async fn state_root(height: u32, ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.state_root(height).or_not_found()?, format))
+    }
+
+    /// Returns a summary of the latest block.
+    async fn latest_summary(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        let ledger = ledger.ledger.read();
+        let block = ledger.latest_block().or_reject()?;
+        Ok(aliased_json(
+            &LatestSummary::<N> {
+                height: block.height(),
+                hash: block.hash(),
+                state_root: *ledger.latest_state_root(),
+                timestamp: block.header().timestamp(),
+                transactions: block.transactions().len(),
+                proof_target: block.header().proof_target(),
+            },
+            format,
+        ))
     }
 
     /// Returns the latest block.
-    async fn latest_block(ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.ledger.read().latest_block().or_reject()?))
+    async fn latest_block(ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.ledger.read().latest_block().or_reject()?, format))
     }
 
     /// Returns the block for the given block height.
-    async fn get_block(height: u32, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(&ledger.ledger.read().get_block(height).or_reject()?))
+    async fn get_block(height: u32, ledger: Arc<Ledger<N>>, format: ResponseFormat) -> Result<impl Reply, Rejection> {
+        Ok(json(&ledger.ledger.read().get_block(height).or_not_found()?, format))
     }
 
     /// Returns the state path for the given commitment.
-    async fn state_path(commitment: Field<N>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
-        Ok(reply::json(
+    async fn state_path(
+        commitment: Field<N>,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        Ok(json(
             &ledger.ledger.read().to_state_path(&commitment).or_reject()?,
+            format,
         ))
     }
 
-    /// Returns all of the records for the given view key.
-    async fn records_all(view_key: ViewKey<N>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+    /// Returns all of the records for the given view key, optionally paginated.
+    async fn records_all(
+        body: serde_json::Value,
+        ledger: Arc<Ledger<N>>,
+        redact_records: bool,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        // Parse the body, which is either the view key or an object with the view key and the page.
+        let (view_key, page) = match body {
+            serde_json::Value::String(view_key) => {
+                (view_key.parse::<ViewKey<N>>().or_reject()?, RecordsPage::default())
+            }
+            body => {
+                let body: IndexMap<String, serde_json::Value> = serde_json::from_value(body)
+                    .map_err(|error| reject::custom(ServerError::Request(error.to_string())))?;
+                (Self::required_field(&body, "view_key")?, Self::records_page(&body)?)
+            }
+        };
         // Fetch the records using the view key.
-        let records: IndexMap<_, _> = ledger
-            .ledger
-            .read()
-            .find_records(&view_key, RecordsFilter::All)
-            .collect();
+        let records = ledger
+            .find_records_page(&view_key, RecordsFilter::All, page)
+            .or_reject()?;
         // Return the records.
-        Ok(reply::with_status(reply::json(&records), StatusCode::OK))
+        Ok(reply::with_status(
+            Self::records_reply(records, redact_records, format),
+            StatusCode::OK,
+        ))
     }
 
-    /// Returns the spent records for the given view key.
-    async fn records_spent(body: IndexMap<String, String>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+    /// Returns the spent records for the given view key, optionally paginated.
+    async fn records_spent(
+        body: IndexMap<String, serde_json::Value>,
+        ledger: Arc<Ledger<N>>,
+        redact_records: bool,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
         // Parse the body.
-        let view_key: ViewKey<N> = body["view_key"].parse().or_reject()?;
-        let graph_key: GraphKey<N> = body["graph_key"].parse().or_reject()?;
+        let view_key: ViewKey<N> = Self::required_field(&body, "view_key")?;
+        let graph_key: GraphKey<N> = Self::required_field(&body, "graph_key")?;
+        let page = Self::records_page(&body)?;
         // Fetch the records using the view key.
         let records = ledger
-            .ledger
-            .read()
-            .find_records(&view_key, RecordsFilter::Spent(graph_key))
-            .collect::<IndexMap<_, _>>();
+            .find_records_page(&view_key, RecordsFilter::Spent(graph_key), page)
+            .or_reject()?;
         // Return the records.
-        Ok(reply::with_status(reply::json(&records), StatusCode::OK))
+        Ok(reply::with_status(
+            Self::records_reply(records, redact_records, format),
+            StatusCode::OK,
+        ))
     }
 
-    /// Returns the unspent records for the given view key.
-    async fn records_unspent(body: IndexMap<String, String>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+    /// Returns the unspent records for the given view key, optionally paginated.
+    async fn records_unspent(
+        body: IndexMap<String, serde_json::Value>,
+        ledger: Arc<Ledger<N>>,
+        redact_records: bool,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
         // Parse the body.
-        let view_key: ViewKey<N> = body["view_key"].parse().or_reject()?;
-        let graph_key: GraphKey<N> = body["graph_key"].parse().or_reject()?;
+        let view_key: ViewKey<N> = Self::required_field(&body, "view_key")?;
+        let graph_key: GraphKey<N> = Self::required_field(&body, "graph_key")?;
+        let page = Self::records_page(&body)?;
         // Fetch the records using the view key.
         let records = ledger
-            .ledger
-            .read()
-            .find_records(&view_key, RecordsFilter::Unspent(graph_key))
-            .collect::<IndexMap<_, _>>();
+            .find_records_page(&view_key, RecordsFilter::Unspent(graph_key), page)
+            .or_reject()?;
         // Return the records.
-        Ok(reply::with_status(reply::json(&records), StatusCode::OK))
+        Ok(reply::with_status(
+            Self::records_reply(records, redact_records, format),
+            StatusCode::OK,
+        ))
+    }
+
+    /// Parses the given field of a request body, which may be a string or a number.
+    fn optional_field<T: FromStr>(body: &IndexMap<String, serde_json::Value>, key: &str) -> Result<Option<T>, Rejection>
+    where
+        T::Err: fmt::Display,
+    {
+        let value = match body.get(key) {
+            None | Some(serde_json::Value::Null) => return Ok(None),
+            Some(serde_json::Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        };
+        value
+            .parse()
+            .map(Some)
+            .map_err(|error| reject::custom(ServerError::Request(format!("Invalid '{key}': {error}"))))
+    }
+
+    /// Parses the given required field of a request body.
+    fn required_field<T: FromStr>(body: &IndexMap<String, serde_json::Value>, key: &str) -> Result<T, Rejection>
+    where
+        T::Err: fmt::Display,
+    {
+        Self::optional_field(body, key)?.ok_or_else(|| reject::custom(ServerError::Request(format!("Missing '{key}'"))))
+    }
+
+    /// Parses the block range and pagination filters of a records request body.
+    fn records_page(body: &IndexMap<String, serde_json::Value>) -> Result<RecordsPage, Rejection> {
+        Ok(RecordsPage {
+            start_height: Self::optional_field(body, "start_height")?,
+            end_height: Self::optional_field(body, "end_height")?,
+            limit: Self::optional_field(body, "limit")?,
+            offset: Self::optional_field(body, "offset")?.unwrap_or(0),
+        })
+    }
+
+    /// Returns the reply for the given records, which only contains their commitments if the records are redacted.
+    fn records_reply<R: Serialize>(
+        records: IndexMap<Field<N>, R>,
+        redact_records: bool,
+        format: ResponseFormat,
+    ) -> reply::Response {
+        match redact_records {
+            true => {
+                let commitments = records.keys().collect::<Vec<_>>();
+                reply::with_header(json(&commitments, format), "X-Aleo-Redacted", "true").into_response()
+            }
+            false => json(&records, format).into_response(),
+        }
+    }
+
+    /// Updates the node settings, and returns the resulting settings.
+    async fn admin_config(update: NodeSettingsUpdate, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+        let mut settings = ledger.settings();
+        if let Some(block_interval_ms) = update.block_interval_ms {
+            settings.block_interval_ms = block_interval_ms;
+        }
+        if let Some(log_level) = update.log_level {
+            settings.log_level = log_level;
+        }
+        if let Some(max_block_transactions) = update.max_block_transactions {
+            settings.max_block_transactions = max_block_transactions;
+        }
+        if let Some(max_pending_per_sender) = update.max_pending_per_sender {
+            settings.max_pending_per_sender = max_pending_per_sender;
+        }
+        if let Some(max_pending_transactions) = update.max_pending_transactions {
+            settings.max_pending_transactions = max_pending_transactions;
+        }
+        if let Some(max_pending_bytes) = update.max_pending_bytes {
+            settings.max_pending_bytes = max_pending_bytes;
+        }
+        if let Some(pending_ttl_secs) = update.pending_ttl_secs {
+            settings.pending_ttl_secs = pending_ttl_secs;
+        }
+        ledger.set_settings(settings.clone());
+        Ok(reply::json(&settings))
+    }
+
+    /// Updates the log level, and returns the resulting settings.
+    async fn admin_log_level(log_level: u8, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+        Self::admin_config(
+            NodeSettingsUpdate {
+                block_interval_ms: None,
+                log_level: Some(log_level),
+                max_block_transactions: None,
+                max_pending_per_sender: None,
+                max_pending_transactions: None,
+                max_pending_bytes: None,
+                pending_ttl_secs: None,
+            },
+            ledger,
+        )
+        .await
+    }
+
+    /// Replaces the faults to inject, and returns them.
+    async fn admin_faults(
+        new_faults: IndexMap<String, Fault>,
+        fault_injection: bool,
+        faults: Faults,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure fault injection is enabled.
+        if !fault_injection {
+            return Err(reject::custom(ServerError::Request(
+                "Fault injection is disabled".to_string(),
+            )));
+        }
+        // Ensure the probabilities are valid.
+        for (prefix, fault) in &new_faults {
+            if !(0.0..=1.0).contains(&fault.error_rate) || !(0.0..=1.0).contains(&fault.drop_rate) {
+                return Err(reject::custom(ServerError::Request(format!(
+                    "The fault rates for '{}' must be between 0 and 1",
+                    prefix
+                ))));
+            }
+        }
+        *faults.write() = new_faults;
+        Ok(reply::json(&*faults.read()))
+    }
+
+    /// Returns the diagnostics of the node.
+    async fn debug(
+        ledger: Arc<Ledger<N>>,
+        ledger_sender: LedgerSender<N>,
+        workers: Arc<Semaphore>,
+        verification_threads: usize,
+        subscriptions: Subscriptions<N>,
+    ) -> Result<impl Reply, Rejection> {
+        let (memory_pool_transactions, memory_pool_bytes) = ledger.memory_pool_usage();
+        Ok(reply::json(&Diagnostics {
+            ledger_queue: Self::LEDGER_QUEUE_CAPACITY - ledger_sender.capacity(),
+            verification_tasks: verification_threads - workers.available_permits(),
+            block_subscribers: ledger.block_subscribers(),
+            record_subscriptions: subscriptions.read().len(),
+            blocks: ledger.ledger.read().latest_height() + 1,
+            memory_pool_transactions,
+            memory_pool_bytes,
+        }))
+    }
+
+    /// Returns the metrics of the node, in the Prometheus text format.
+    async fn metrics(handle: PrometheusHandle) -> Result<impl Reply, Rejection> {
+        Ok(reply::with_header(
+            handle.render(),
+            "Content-Type",
+            "text/plain; version=0.0.4",
+        ))
+    }
+
+    /// Registers the view key for record notifications, and returns the subscription ID.
+    async fn subscription_register(
+        view_key: ViewKey<N>,
+        subscriptions: Subscriptions<N>,
+    ) -> Result<impl Reply, Rejection> {
+        let id = format!("{:032x}", rand::thread_rng().gen::<u128>());
+        subscriptions.write().insert(id.clone(), view_key);
+        Ok(reply::json(&Subscription { id }))
+    }
+
+    /// Unregisters the subscription, which also ends its notification stream.
+    async fn subscription_unregister(id: String, subscriptions: Subscriptions<N>) -> Result<impl Reply, Rejection> {
+        match subscriptions.write().shift_remove(&id) {
+            Some(_) => Ok("OK"),
+            None => Err(reject::not_found()),
+        }
+    }
+
+    /// Streams the records of the subscribed view key, as server-sent events for each new block
+    /// that contains any of them.
+    async fn subscription_records(
+        id: String,
+        subscriptions: Subscriptions<N>,
+        ledger: Arc<Ledger<N>>,
+        redact_records: bool,
+        mut shutdown_receiver: watch::Receiver<bool>,
+    ) -> Result<impl Reply, Rejection> {
+        // Fetch the subscribed view key.
+        let view_key = subscriptions.read().get(&id).cloned().ok_or_else(reject::not_found)?;
+        // Subscribe to the new blocks.
+        let blocks = ledger.subscribe_blocks();
+
+        let events = stream::unfold(blocks, move |mut blocks| {
+            let (id, view_key, subscriptions, ledger) =
+                (id.clone(), view_key.clone(), subscriptions.clone(), ledger.clone());
+            async move {
+                loop {
+                    // Wait for the next block; a lagging subscriber only misses the skipped blocks.
+                    let block = match blocks.recv().await {
+                        Ok(block) => block,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    };
+                    // End the stream once the subscription is unregistered.
+                    if !subscriptions.read().contains_key(&id) {
+                        return None;
+                    }
+                    // Find the records of the view key in the block.
+                    let records = match ledger.find_block_records(&block, &view_key) {
+                        Ok(records) => records,
+                        Err(error) => {
+                            eprintln!("Failed to find the subscription records: {error}");
+                            continue;
+                        }
+                    };
+                    if records.is_empty() {
+                        continue;
+                    }
+                    let event = match redact_records {
+                        true => sse::Event::default().json_data(records.keys().collect::<Vec<_>>()),
+                        false => sse::Event::default().json_data(&records),
+                    };
+                    match event {
+                        Ok(event) => return Some((Ok::<_, Infallible>(event.event("records")), blocks)),
+                        Err(error) => eprintln!("Failed to serialize the subscription records: {error}"),
+                    }
+                }
+            }
+        });
+        // End the stream on shutdown, as the server waits for the open connections to close.
+        let shutdown = async move {
+            let _ = shutdown_receiver.changed().await;
+        };
+        Ok(sse::reply(sse::keep_alive().stream(events.take_until(shutdown))))
+    }
+
+    /// Verifies the transaction against the current ledger, without broadcasting it.
+    async fn execution_verify(transaction: Transaction<N>, ledger: Arc<Ledger<N>>) -> Result<impl Reply, Rejection> {
+        let valid = tokio::task::spawn_blocking(move || ledger.verify_transaction(&transaction))
+            .await
+            .map_err(|error| reject::custom(ServerError::Internal(format!("{error}"))))?;
+        Ok(reply::json(&Verification { valid }))
+    }
+
+    /// Executes the given program function with the node account against the current ledger state,
+    /// and returns the execution without broadcasting it.
+    async fn execute_dry_run(
+        request: DryRunRequest,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        // Parse the request.
+        let parse = || -> Result<_> {
+            let program_id = ProgramID::<N>::from_str(&request.program_id)?;
+            let function = Identifier::<N>::from_str(&request.function)?;
+            let inputs = request
+                .inputs
+                .iter()
+                .map(|input| Value::from_str(input))
+                .collect::<Result<Vec<_>>>()?;
+            Ok((program_id, function, inputs))
+        };
+        let (program_id, function, inputs) = parse().or_reject()?;
+        // Ensure the program exists.
+        if ledger.find_program(&program_id).or_reject()?.is_none() {
+            return Err(reject::custom(ServerError::NotFound(format!(
+                "Program '{program_id}' does not exist"
+            ))));
+        }
+        // Execute the function, which proves it, off the async runtime.
+        let execute = move || -> Result<DryRun<N>> {
+            let transaction = ledger.create_execute(&program_id, function, &inputs, None)?;
+            Ok(DryRun {
+                transitions: transaction.transitions().cloned().collect(),
+                fee: u64::try_from(transaction.fee()?).unwrap_or(0),
+                size_in_bytes: transaction.to_bytes_le()?.len(),
+            })
+        };
+        let dry_run = tokio::task::spawn_blocking(execute)
+            .await
+            .map_err(|error| reject::custom(ServerError::Internal(format!("{error}"))))?
+            .or_reject()?;
+        Ok(aliased_json(&dry_run, format))
+    }
+
+    /// Validates the deployment-specific rules of the transaction, and broadcasts it to the ledger.
+    async fn deploy(
+        transaction: Transaction<N>,
+        remote: Option<SocketAddr>,
+        ledger_sender: LedgerSender<N>,
+        replay_window: Arc<ReplayWindow<N::TransactionID>>,
+        ledger: Arc<Ledger<N>>,
+    ) -> Result<impl Reply, Rejection> {
+        // Ensure the transaction is a deployment.
+        let program_id = match &transaction {
+            Transaction::Deploy(_, deployment, _) => *deployment.program_id(),
+            _ => {
+                return Err(reject::custom(ServerError::InvalidTransaction(
+                    "The transaction is not a deployment".to_string(),
+                )));
+            }
+        };
+        // Ensure the program does not exist yet.
+        if ledger.contains_program(&program_id) {
+            return Err(reject::custom(ServerError::Conflict(format!(
+                "Program '{program_id}' already exists"
+            ))));
+        }
+        // Broadcast the deployment.
+        Self::transaction_broadcast(transaction, remote, ledger_sender, replay_window, ledger).await
+    }
+
+    /// Creates a transfer from the node account that fulfills the payment request, broadcasts it to the ledger,
+    /// and returns its transaction ID.
+    async fn payment_fulfill(
+        request: PaymentRequest<N>,
+        remote: Option<SocketAddr>,
+        ledger_sender: LedgerSender<N>,
+        replay_window: Arc<ReplayWindow<N::TransactionID>>,
+        ledger: Arc<Ledger<N>>,
+    ) -> Result<impl Reply, Rejection> {
+        // Create the transfer, which proves it, off the async runtime.
+        let fulfill = {
+            let ledger = ledger.clone();
+            move || ledger.fulfill_payment(&request)
+        };
+        let transaction = tokio::task::spawn_blocking(fulfill)
+            .await
+            .map_err(|error| reject::custom(ServerError::Internal(format!("{error}"))))?
+            .or_reject()?;
+        let transaction_id = transaction.id();
+        Self::transaction_broadcast(transaction, remote, ledger_sender, replay_window, ledger).await?;
+        Ok(reply::json(&transaction_id))
+    }
+
+    /// Returns the payment to the node account that fulfills the payment request, or `null` if it was not paid.
+    async fn payment_verify(
+        request: PaymentRequest<N>,
+        ledger: Arc<Ledger<N>>,
+        format: ResponseFormat,
+    ) -> Result<impl Reply, Rejection> {
+        let receipt = ledger.find_payment(&request, ledger.view_key()).or_reject()?;
+        Ok(json(&receipt, format))
     }
 
     /// Broadcasts the transaction to the ledger.
     async fn transaction_broadcast(
         transaction: Transaction<N>,
+        remote: Option<SocketAddr>,
         ledger_sender: LedgerSender<N>,
+        replay_window: Arc<ReplayWindow<N::TransactionID>>,
+        ledger: Arc<Ledger<N>>,
     ) -> Result<impl Reply, Rejection> {
+        // Reject the transaction if it was already broadcast within the window, with the status of the original.
+        let transaction_id = transaction.id();
+        if let Some(queued) = replay_window.check_and_insert(transaction_id, ledger.clock().now(), "queued") {
+            // The ledger does not know the transaction while it waits in the ledger queue.
+            let status = match ledger.transaction_status(&transaction_id) {
+                Ok(TransactionStatus::Unknown) => queued,
+                Ok(status) => status.to_string(),
+                Err(error) => return Err(reject::custom(ServerError::Internal(error.to_string()))),
+            };
+            return Err(reject::custom(ServerError::Replay(status)));
+        }
+        // Identify the sender by its IP address.
+        let sender = remote
+            .map(|remote| remote.ip().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
         // Send the transaction to the ledger.
         match ledger_sender
-            .send(LedgerRequest::TransactionBroadcast(transaction))
+            .send(LedgerRequest::TransactionBroadcast(transaction, sender))
             .await
         {
             Ok(()) => Ok("OK"),
-            Err(error) => Err(reject::custom(ServerError::Request(format!("{error}")))),
+            Err(error) => {
+                // The transaction was not consumed, so it may be retried.
+                replay_window.remove(&transaction_id);
+                Err(reject::custom(ServerError::Unavailable(format!("{error}"))))
+            }
         }
     }
 }
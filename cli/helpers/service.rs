@@ -0,0 +1,99 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Notifies the service manager of the given state (e.g. `READY=1`), if the process runs under systemd.
+/// This is a no-op when `NOTIFY_SOCKET` is not set, or on platforms without Unix sockets.
+pub fn notify(state: &str) -> Result<()> {
+    #[cfg(unix)]
+    if let Some(socket) = std::env::var_os("NOTIFY_SOCKET") {
+        use std::os::unix::{ffi::OsStrExt, net::UnixDatagram};
+
+        // Sockets in the abstract namespace are prefixed with `@`, which is a null byte for the kernel.
+        let mut path = socket.as_bytes().to_vec();
+        if path.first() == Some(&b'@') {
+            path[0] = 0;
+        }
+        let path: &Path = std::ffi::OsStr::from_bytes(&path).as_ref();
+        UnixDatagram::unbound()?.send_to(state.as_bytes(), path)?;
+    }
+    #[cfg(not(unix))]
+    let _ = state;
+    Ok(())
+}
+
+/// Returns a systemd unit file that runs the given command line in the given directory.
+pub fn systemd_unit<S: AsRef<str>>(description: &str, working_directory: &Path, command: &[S]) -> String {
+    let command = command
+        .iter()
+        .map(|arg| systemd_quote(arg.as_ref()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        r#"[Unit]
+Description={description}
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=notify
+WorkingDirectory={}
+ExecStart={command}
+Restart=on-failure
+KillSignal=SIGTERM
+TimeoutStopSec=30
+
+[Install]
+WantedBy=multi-user.target
+"#,
+        // Escape the specifiers, which systemd expands in the path.
+        working_directory.display().to_string().replace('%', "%%")
+    )
+}
+
+/// Quotes the given command line argument for `ExecStart=`, so that it is passed as it is.
+fn systemd_quote(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    for c in arg.chars() {
+        match c {
+            // Escape the specifiers and environment variables, which systemd expands in the command line.
+            '%' => quoted.push_str("%%"),
+            '$' => quoted.push_str("$$"),
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_quote() {
+        assert_eq!(r#""aleo""#, systemd_quote("aleo"));
+        assert_eq!(r#""/opt/my apps/aleo""#, systemd_quote("/opt/my apps/aleo"));
+        assert_eq!(r#""say \"hi\" \\ bye""#, systemd_quote(r#"say "hi" \ bye"#));
+        assert_eq!(r#""100%% $$HOME""#, systemd_quote("100% $HOME"));
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::helpers::{Ledger, RecordEntries};
+use snarkvm::prelude::{
+    Field,
+    Identifier,
+    Network,
+    Plaintext,
+    ProgramID,
+    Record,
+    RecordsFilter,
+    Transaction,
+    Value,
+    ViewKey,
+};
+
+use anyhow::{ensure, Result};
+use core::str::FromStr;
+use indexmap::IndexMap;
+
+/// A vesting stream record belonging to a conforming program.
+///
+/// A conforming program defines a record with `start_height` and `end_height` u32 entries, and `total`
+/// and `claimed` u64 entries, which vests the total linearly per block between the two heights,
+/// and exposes the following function:
+///   - `claim(stream: record, amount: u64)`, which pays the amount to the owner and returns the stream
+///     with the amount added to `claimed`
+///
+/// The stream state is kept in the record rather than in a program mapping, as mappings are not
+/// supported by this version of snarkVM, so the claimable amount is computed from the record entries.
+#[derive(Clone, Debug)]
+pub struct VestingStream<N: Network> {
+    /// The program ID of the vesting program.
+    program_id: ProgramID<N>,
+    /// The record commitment.
+    commitment: Field<N>,
+    /// The height at which the stream starts vesting.
+    start_height: u32,
+    /// The height at which the stream is fully vested.
+    end_height: u32,
+    /// The total amount of the stream.
+    total: u64,
+    /// The amount claimed so far.
+    claimed: u64,
+    /// The record.
+    record: Record<N, Plaintext<N>>,
+}
+
+impl<N: Network> VestingStream<N> {
+    /// The name of the claim function.
+    const CLAIM: &'static str = "claim";
+
+    /// Parses a vesting stream from the given record, returning an error if it does not conform.
+    pub fn from_record(
+        program_id: ProgramID<N>,
+        commitment: Field<N>,
+        record: Record<N, Plaintext<N>>,
+    ) -> Result<Self> {
+        let entries = RecordEntries::new(commitment, &record);
+
+        let start_height = entries.integer("start_height", "u32")? as u32;
+        let end_height = entries.integer("end_height", "u32")? as u32;
+        ensure!(
+            start_height <= end_height,
+            "Record {commitment} ends vesting at {end_height}, before it starts at {start_height}"
+        );
+        Ok(Self {
+            program_id,
+            commitment,
+            start_height,
+            end_height,
+            total: entries.integer("total", "u64")?,
+            claimed: entries.integer("claimed", "u64")?,
+            record,
+        })
+    }
+
+    /// Returns the program ID of the vesting program.
+    pub const fn program_id(&self) -> &ProgramID<N> {
+        &self.program_id
+    }
+
+    /// Returns the record commitment.
+    pub const fn commitment(&self) -> &Field<N> {
+        &self.commitment
+    }
+
+    /// Returns the total amount of the stream.
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns the amount claimed so far.
+    pub const fn claimed(&self) -> u64 {
+        self.claimed
+    }
+
+    /// Returns the record.
+    pub const fn record(&self) -> &Record<N, Plaintext<N>> {
+        &self.record
+    }
+
+    /// Returns the amount vested at the given block height.
+    pub fn vested(&self, height: u32) -> u64 {
+        vested_amount(self.total, self.start_height, self.end_height, height)
+    }
+
+    /// Returns the amount that can be claimed at the given block height.
+    pub fn claimable(&self, height: u32) -> u64 {
+        self.vested(height).saturating_sub(self.claimed)
+    }
+}
+
+/// Returns the amount of the total vested at the given height, vesting linearly per block
+/// from the start height until it is fully vested at the end height.
+fn vested_amount(total: u64, start_height: u32, end_height: u32, height: u32) -> u64 {
+    if height >= end_height {
+        return total;
+    }
+    if height <= start_height {
+        return 0;
+    }
+    let elapsed = u128::from(height - start_height);
+    let duration = u128::from(end_height - start_height);
+    // The product cannot overflow, and the quotient is at most the total.
+    (u128::from(total) * elapsed / duration) as u64
+}
+
+impl<N: Network> Ledger<N> {
+    /// Returns the vesting streams of the given program that belong to the given view key.
+    pub fn find_vesting_streams(
+        &self,
+        program_id: &ProgramID<N>,
+        view_key: &ViewKey<N>,
+        filter: RecordsFilter<N>,
+    ) -> Result<IndexMap<Field<N>, VestingStream<N>>> {
+        let commitments = self.program_commitments(program_id);
+        // Fetch the records of the view key, and parse the ones produced by the vesting program,
+        // skipping the program's other records.
+        Ok(self
+            .ledger
+            .read()
+            .find_records(view_key, filter)
+            .filter(|(commitment, _)| commitments.contains_key(commitment))
+            .filter_map(|(commitment, record)| {
+                let stream = VestingStream::from_record(*program_id, commitment, record).ok()?;
+                Some((commitment, stream))
+            })
+            .collect())
+    }
+
+    /// Creates a transaction claiming the amount of the given stream that is vested at the latest height.
+    ///
+    /// The stream is expected to be unspent; a claim of a spent stream is rejected by the ledger.
+    pub fn create_vesting_claim(&self, stream: &VestingStream<N>) -> Result<Transaction<N>> {
+        // Ensure there is an amount to claim.
+        let claimable = stream.claimable(self.ledger.read().latest_height());
+        ensure!(claimable > 0, "Stream {} has nothing to claim yet", stream.commitment());
+
+        self.create_execute(
+            stream.program_id(),
+            Identifier::from_str(VestingStream::<N>::CLAIM)?,
+            &[
+                Value::Record(stream.record().clone()),
+                Value::from_str(&format!("{claimable}u64"))?,
+            ],
+            None,
+        )
+    }
+
+    /// Creates the transactions claiming the vested amounts of all of the account's unspent streams
+    /// of the given program, skipping the streams with nothing to claim.
+    pub fn create_vesting_claims(&self, program_id: &ProgramID<N>) -> Result<Vec<Transaction<N>>> {
+        let latest_height = self.ledger.read().latest_height();
+        self.find_vesting_streams(program_id, self.view_key(), self.unspent_filter())?
+            .values()
+            .filter(|stream| stream.claimable(latest_height) > 0)
+            .map(|stream| self.create_vesting_claim(stream))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vested_amount() {
+        // Nothing vests before the start, and everything vests at the end.
+        assert_eq!(0, vested_amount(1_000, 10, 20, 0));
+        assert_eq!(0, vested_amount(1_000, 10, 20, 10));
+        assert_eq!(1_000, vested_amount(1_000, 10, 20, 20));
+        assert_eq!(1_000, vested_amount(1_000, 10, 20, u32::MAX));
+
+        // The total vests linearly per block, rounding down.
+        assert_eq!(100, vested_amount(1_000, 10, 20, 11));
+        assert_eq!(500, vested_amount(1_000, 10, 20, 15));
+        assert_eq!(333, vested_amount(1_000, 0, 3, 1));
+
+        // Large totals do not overflow.
+        assert_eq!(u64::MAX / 2, vested_amount(u64::MAX, 0, 2, 1));
+
+        // A stream that starts and ends at the same height vests at once.
+        assert_eq!(1_000, vested_amount(1_000, 10, 10, 10));
+    }
+}
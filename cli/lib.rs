@@ -20,6 +20,7 @@ extern crate thiserror;
 pub mod commands;
 pub mod errors;
 pub mod helpers;
+pub mod testing;
 
 pub(crate) type Network = snarkvm::prelude::Testnet3;
 pub(crate) type Aleo = snarkvm::circuit::AleoV0;
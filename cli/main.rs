@@ -14,19 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
 
-use aleo::{commands::CLI, helpers::Updater};
+use aleo::{commands::CLI, errors::ExitCode, helpers::Updater};
 
 use clap::Parser;
 
 fn main() -> anyhow::Result<()> {
     // Parse the given arguments.
     let cli = CLI::parse();
-    // Run the updater.
-    println!("{}", Updater::print_cli());
+    // Run the updater, unless in quiet mode.
+    match cli.quiet {
+        true => colored::control::set_override(false),
+        false => println!("{}", Updater::print_cli()),
+    }
     // Run the CLI.
     match cli.command.parse() {
-        Ok(output) => println!("{output}\n"),
-        Err(error) => println!("⚠️  {error}\n"),
+        Ok(output) => match cli.quiet {
+            true => println!("{}", output.trim()),
+            false => println!("{output}\n"),
+        },
+        Err(error) => {
+            match cli.quiet {
+                true => eprintln!("{error}"),
+                false => println!("⚠️  {error}\n"),
+            }
+            std::process::exit(ExitCode::from_error(&error) as i32);
+        }
     }
     Ok(())
 }
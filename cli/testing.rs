@@ -0,0 +1,84 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+//! Utilities to generate random valid chains, so downstream code can be property-tested against this crate.
+
+use crate::helpers::{DustPolicy, Ledger};
+use snarkvm::prelude::{Address, Block, Network, PrivateKey, Transaction};
+
+use anyhow::{ensure, Result};
+use rand::{CryptoRng, Rng};
+use std::{convert::TryFrom, sync::Arc};
+
+/// Samples a new private key.
+pub fn sample_private_key<N: Network, R: Rng + CryptoRng>(rng: &mut R) -> Result<PrivateKey<N>> {
+    PrivateKey::new(rng)
+}
+
+/// Samples the given number of private keys, and returns them with their addresses.
+pub fn sample_accounts<N: Network, R: Rng + CryptoRng>(
+    num_accounts: usize,
+    rng: &mut R,
+) -> Result<Vec<(PrivateKey<N>, Address<N>)>> {
+    (0..num_accounts)
+        .map(|_| {
+            let private_key = sample_private_key(rng)?;
+            let address = Address::try_from(&private_key)?;
+            Ok((private_key, address))
+        })
+        .collect()
+}
+
+/// Samples a transfer of a random amount (between 1 and `max_amount` gates) from the ledger account
+/// to a random recipient.
+pub fn sample_transfer<N: Network, R: Rng>(
+    ledger: &Ledger<N>,
+    recipients: &[Address<N>],
+    max_amount: u64,
+    rng: &mut R,
+) -> Result<Transaction<N>> {
+    ensure!(!recipients.is_empty(), "At least one recipient is required");
+    ensure!(max_amount > 0, "The maximum amount must be positive");
+
+    let recipient = &recipients[rng.gen_range(0..recipients.len())];
+    let amount = rng.gen_range(1..=max_amount);
+    ledger.create_transfer(recipient, amount, DustPolicy::Allow)
+}
+
+/// Samples a chain of `num_blocks` blocks after genesis, where each block contains
+/// a random transfer from the ledger account to one of the given recipients.
+///
+/// The ledger is not served, and its blocks are returned in order, starting with the genesis block.
+pub fn sample_chain<N: Network, R: Rng>(
+    private_key: &PrivateKey<N>,
+    recipients: &[Address<N>],
+    num_blocks: u32,
+    rng: &mut R,
+) -> Result<(Arc<Ledger<N>>, Vec<Block<N>>)> {
+    // Initialize the ledger.
+    let ledger = Ledger::new(private_key)?;
+    let mut blocks = vec![ledger.ledger.read().get_block(0)?];
+
+    for _ in 0..num_blocks {
+        // Add a random transfer to the memory pool.
+        let transaction = sample_transfer(&ledger, recipients, 1_000, rng)?;
+        ledger.add_to_memory_pool(transaction)?;
+        // Advance to the next block.
+        blocks.push(ledger.advance_to_next_block()?);
+    }
+
+    Ok((ledger, blocks))
+}
@@ -0,0 +1,30 @@
+// Copyright (C) 2019-2021 Aleo Systems Inc.
+// This file is part of the Aleo library.
+
+// The Aleo library is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// The Aleo library is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with the Aleo library. If not, see <https://www.gnu.org/licenses/>.
+
+#![no_main]
+
+use aleo::helpers::NodeInfo;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (NodeInfo, &[u8])| {
+    let (node_info, bytes) = input;
+    // Every node info must round-trip through JSON.
+    let json = serde_json::to_vec(&node_info).unwrap();
+    assert_eq!(node_info, serde_json::from_slice::<NodeInfo>(&json).unwrap());
+    // Parsing arbitrary bytes must never panic.
+    let _ = serde_json::from_slice::<NodeInfo>(bytes);
+});